@@ -0,0 +1,102 @@
+//! The argument-parsing/stdin/exit-code boilerplate every standalone day binary used to hand-roll
+//! for itself: read `args.get(1)`, match it against `part-one`/`part-two`, slurp stdin, then print
+//! the answer or print the error to stderr and exit non-zero. Days not yet migrated to the
+//! `Solution`/`Registry` machinery in `crate::days` can use [`run`] instead of repeating all of
+//! that in their own `main`.
+
+use crate::QuestionPart;
+use std::env;
+use std::fmt;
+use std::io::{stdin, Read};
+use std::process::exit;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum CliError {
+    #[error("Invalid command `{command:?}'. Expected `part-one' or `part-two'.")]
+    InvalidCommand { command: String },
+
+    #[error("Please specify `part-one' or `part-two' as the first argument.")]
+    NoPartArgument,
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Either part's answer, as whatever `Display`-able value the day computed. Kept as a string
+/// rather than a day-specific type since what each part returns (a count, a decoded word, ...)
+/// varies from day to day.
+pub enum Answer {
+    PartOne(String),
+    PartTwo(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::PartOne(s) | Answer::PartTwo(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+/// The parsed `part-one`/`part-two` selector plus every other raw argument, so a day that needs an
+/// extra flag (e.g. `--raw`) can still check for it with [`AdventArgs::has_flag`].
+pub struct AdventArgs {
+    pub question_part: QuestionPart,
+    args: Vec<String>,
+}
+
+impl AdventArgs {
+    pub fn parse() -> Result<Self, CliError> {
+        let args: Vec<String> = env::args().collect();
+        let command = args.get(1).ok_or(CliError::NoPartArgument)?;
+        let question_part = match &command[..] {
+            "part-one" => QuestionPart::One,
+            "part-two" => QuestionPart::Two,
+            _ => {
+                return Err(CliError::InvalidCommand {
+                    command: command.to_string(),
+                })
+            }
+        };
+        Ok(Self { question_part, args })
+    }
+
+    pub fn has_flag(&self, flag: &str) -> bool {
+        self.args.iter().any(|arg| arg == flag)
+    }
+
+    /// The argument immediately following `flag`, e.g. `flag_value("--image")` returns
+    /// `Some("out.png")` for `... --image out.png`. `None` if `flag` is absent or is the last arg.
+    pub fn flag_value(&self, flag: &str) -> Option<&str> {
+        let index = self.args.iter().position(|arg| arg == flag)?;
+        self.args.get(index + 1).map(String::as_str)
+    }
+}
+
+fn read_stdin() -> Result<String, CliError> {
+    let mut input = String::new();
+    stdin().lock().read_to_string(&mut input)?;
+    Ok(input)
+}
+
+/// Parse the CLI args, read the puzzle input from stdin, run `solve`, then print the answer or
+/// print the error to stderr and exit non-zero.
+pub fn run<E>(solve: impl FnOnce(&AdventArgs, String) -> Result<Answer, E>)
+where
+    E: fmt::Display + From<CliError>,
+{
+    let result = (|| -> Result<Answer, E> {
+        let args = AdventArgs::parse().map_err(E::from)?;
+        let input = read_stdin().map_err(E::from)?;
+        solve(&args, input)
+    })();
+
+    match result {
+        Ok(answer) => println!("{answer}"),
+        Err(err) => {
+            eprintln!("{err}");
+            exit(1);
+        }
+    }
+}
@@ -0,0 +1,125 @@
+//! Day 4: Giant Squid.
+
+use crate::{AdventError, Solution};
+use itertools::chain;
+use ndarray::{Array, Ix2, ShapeError};
+use regex::Regex;
+use std::collections::HashMap;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+enum Day04Error {
+    #[error(transparent)]
+    Regex(#[from] regex::Error),
+
+    #[error(transparent)]
+    Shape(#[from] ShapeError),
+
+    #[error("List of draws is improperly formatted or missing")]
+    DrawsFormat,
+
+    #[error("The provided input never has a solution (not all numbers are drawn)")]
+    NoSolution,
+}
+
+impl From<Day04Error> for AdventError {
+    fn from(err: Day04Error) -> Self {
+        AdventError::Solve(err.to_string())
+    }
+}
+
+/// Map each drawn number to the (0-indexed) turn it's drawn on.
+fn turn_of_number(draws: &[i32]) -> HashMap<i32, usize> {
+    draws.iter().enumerate().map(|(turn, &n)| (n, turn)).collect()
+}
+
+/// The turn on which `number` is drawn, or later than every real turn if it never is (a board
+/// holding such a number can never win on it).
+fn turn(turns: &HashMap<i32, usize>, number: i32) -> usize {
+    turns.get(&number).copied().unwrap_or(usize::MAX)
+}
+
+/// The turn on which `board` completes a row or column: the turn its last-drawn cell in that
+/// line is drawn, minimized over every row and column (whichever line fills up first).
+fn win_turn(board: &Array<i32, Ix2>, turns: &HashMap<i32, usize>) -> usize {
+    chain!(board.rows(), board.columns())
+        .map(|line| line.iter().map(|&n| turn(turns, n)).max().unwrap_or(usize::MAX))
+        .min()
+        .unwrap_or(usize::MAX)
+}
+
+/// `draw_at(win_turn) * (sum of cells not yet drawn at win_turn)`, per the puzzle's scoring rule.
+fn score(board: &Array<i32, Ix2>, win_turn: usize, turns: &HashMap<i32, usize>, draws: &[i32]) -> i32 {
+    let unmarked: i32 = board.iter().filter(|&&n| turn(turns, n) > win_turn).sum();
+    draws[win_turn] * unmarked
+}
+
+fn find_winner(
+    boards: &[Array<i32, Ix2>],
+    draws: &[i32],
+    question_part: crate::QuestionPart,
+) -> Result<i32, Day04Error> {
+    let turns = turn_of_number(draws);
+    let win_turns: Vec<usize> = boards.iter().map(|board| win_turn(board, &turns)).collect();
+
+    let winner = match question_part {
+        crate::QuestionPart::One => win_turns.iter().enumerate().min_by_key(|(_, &turn)| turn),
+        crate::QuestionPart::Two => win_turns.iter().enumerate().max_by_key(|(_, &turn)| turn),
+    };
+    let (index, &win_turn) = winner.ok_or(Day04Error::NoSolution)?;
+    if win_turn == usize::MAX {
+        return Err(Day04Error::NoSolution);
+    }
+
+    Ok(score(&boards[index], win_turn, &turns, draws))
+}
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Parsed = (Vec<Array<i32, Ix2>>, Vec<i32>);
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AdventError> {
+        let lines: Vec<&str> = input.lines().collect();
+        let mut lines = lines.into_iter();
+
+        // First line of input holds all the draws
+        let draws: Vec<i32> = lines
+            .next()
+            .ok_or(Day04Error::DrawsFormat)?
+            .split(',')
+            .filter_map(|x| x.parse().ok())
+            .collect();
+
+        // Parse boards: groups of lines separated by a blank line. Boards are square, so a
+        // group's own line count doubles as its column count, instead of a hard-coded size.
+        let re = Regex::new(r"\s+").map_err(Day04Error::from)?;
+        let lines: Vec<&str> = lines.collect();
+        let boards = lines
+            .split(|line| line.trim().is_empty())
+            .filter(|group| !group.is_empty())
+            .map(|group| {
+                let board_size = group.len();
+                Array::<i32, _>::from_iter(
+                    group
+                        .iter()
+                        .flat_map(|line| re.split(line))
+                        .filter(|x| x != &"")
+                        .filter_map(|x| x.parse().ok()),
+                )
+                .into_shape((board_size, board_size))
+                .map_err(Day04Error::from)
+            })
+            .collect::<Result<Vec<Array<i32, Ix2>>, Day04Error>>()?;
+
+        Ok((boards, draws))
+    }
+
+    fn part_one(&self, (boards, draws): &Self::Parsed) -> Result<String, AdventError> {
+        Ok(find_winner(boards, draws, crate::QuestionPart::One)?.to_string())
+    }
+
+    fn part_two(&self, (boards, draws): &Self::Parsed) -> Result<String, AdventError> {
+        Ok(find_winner(boards, draws, crate::QuestionPart::Two)?.to_string())
+    }
+}
@@ -0,0 +1,151 @@
+//! Day 7: The Treachery of Whales.
+
+use crate::{AdventError, Solution};
+use memoize::memoize;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+enum Day07Error {
+    #[error("Could not parse `{x}' in input string into int.")]
+    Parse { x: String },
+
+    // One line of input was given but it's empty
+    #[error("There are no crabs to help you escape! (empty input)")]
+    NoCrabs,
+}
+
+impl From<Day07Error> for AdventError {
+    fn from(err: Day07Error) -> Self {
+        AdventError::Solve(err.to_string())
+    }
+}
+
+#[memoize]
+fn fuel_for_distance_part_two(n: usize) -> usize {
+    // In part two, the fuel required while taking the nth step is n
+    // Therefore, the fuel required to go n steps is the fuel required to go n-1 steps + n
+    match n {
+        0 => 0,
+        1 => 1,
+        _ => fuel_for_distance_part_two(n - 1) + n,
+    }
+}
+
+fn abs_diff<T: std::cmp::PartialOrd + std::ops::Sub<Output = T>>(a: T, b: T) -> T {
+    // Calculate the absolute difference between unsigned integers
+    // They are unsigned, so we can't do (a - b).abs()
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+// Brute-force: try every position in range and keep the cheapest. Kept around (unused by the
+// `Solution` impl below, which uses the closed-form optima instead) so tests can check the
+// closed-form answers against it.
+#[cfg(test)]
+fn solve_brute_force(
+    initial_state: &[usize],
+    fuel_for_distance: impl Fn(usize) -> usize,
+) -> Result<usize, Day07Error> {
+    let min = *initial_state.iter().min().ok_or(Day07Error::NoCrabs)?;
+    let max = *initial_state.iter().max().ok_or(Day07Error::NoCrabs)?;
+    (min..=max)
+        .map(|align_position| {
+            initial_state
+                .iter()
+                .map(|x| fuel_for_distance(abs_diff(*x, align_position)))
+                .sum::<usize>()
+        })
+        .min()
+        .ok_or(Day07Error::NoCrabs)
+}
+
+/// Total fuel to align every crab on `align_position`, under the given cost-per-step function.
+fn fuel_to_align(initial_state: &[usize], align_position: usize, fuel_for_distance: impl Fn(usize) -> usize) -> usize {
+    initial_state
+        .iter()
+        .map(|x| fuel_for_distance(abs_diff(*x, align_position)))
+        .sum()
+}
+
+/// Part one's cost per step is linear (`|x - p|`), which is minimized at the median: sort and
+/// take the middle element.
+fn median(initial_state: &[usize]) -> Result<usize, Day07Error> {
+    let mut sorted = initial_state.to_vec();
+    sorted.sort_unstable();
+    sorted.get(sorted.len() / 2).copied().ok_or(Day07Error::NoCrabs)
+}
+
+/// Part two's cost per step is the triangular number `d(d+1)/2`, which is convex in `p` and
+/// minimized within `[floor(mean), ceil(mean)]`, so only those two candidates need checking.
+fn mean_candidates(initial_state: &[usize]) -> Result<(usize, usize), Day07Error> {
+    if initial_state.is_empty() {
+        return Err(Day07Error::NoCrabs);
+    }
+    let sum: usize = initial_state.iter().sum();
+    let mean = sum as f64 / initial_state.len() as f64;
+    Ok((mean.floor() as usize, mean.ceil() as usize))
+}
+
+pub struct Day07;
+
+impl Solution for Day07 {
+    type Parsed = Vec<usize>;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AdventError> {
+        if input.trim().is_empty() {
+            return Err(Day07Error::NoCrabs.into());
+        }
+
+        // Input is one line of int separated by comma
+        input
+            .trim()
+            .split(',')
+            .map(|x| {
+                x.parse()
+                    .map_err(|_| Day07Error::Parse { x: x.to_string() })
+            })
+            .collect::<Result<Vec<usize>, Day07Error>>()
+            .map_err(AdventError::from)
+    }
+
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<String, AdventError> {
+        // Fuel for n steps in part one is just n, minimized by aligning on the median.
+        let align_position = median(parsed)?;
+        Ok(fuel_to_align(parsed, align_position, std::convert::identity).to_string())
+    }
+
+    fn part_two(&self, parsed: &Self::Parsed) -> Result<String, AdventError> {
+        // Fuel for n steps in part two is triangular, minimized by one of the two integers
+        // bracketing the mean.
+        let (floor, ceil) = mean_candidates(parsed)?;
+        let fuel = fuel_to_align(parsed, floor, fuel_for_distance_part_two)
+            .min(fuel_to_align(parsed, ceil, fuel_for_distance_part_two));
+        Ok(fuel.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "16,1,2,0,4,2,7,1,2,14";
+
+    #[test]
+    fn part_one_matches_brute_force() {
+        let parsed = Day07.parse(INPUT).unwrap();
+        let brute_force = solve_brute_force(&parsed, std::convert::identity).unwrap();
+        assert_eq!(Day07.part_one(&parsed).unwrap(), brute_force.to_string());
+        assert_eq!(brute_force, 37);
+    }
+
+    #[test]
+    fn part_two_matches_brute_force() {
+        let parsed = Day07.parse(INPUT).unwrap();
+        let brute_force = solve_brute_force(&parsed, fuel_for_distance_part_two).unwrap();
+        assert_eq!(Day07.part_two(&parsed).unwrap(), brute_force.to_string());
+        assert_eq!(brute_force, 168);
+    }
+}
@@ -0,0 +1,180 @@
+//! Day 8: Seven Segment Search.
+
+use crate::{AdventError, Solution};
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+use thiserror::Error;
+
+type Digit = HashSet<char>;
+type Entry = (Vec<String>, Vec<String>);
+
+#[derive(Error, Debug)]
+enum Day08Error {
+    #[error("Invalid input")]
+    InvalidInput,
+}
+
+impl From<Day08Error> for AdventError {
+    fn from(err: Day08Error) -> Self {
+        AdventError::Solve(err.to_string())
+    }
+}
+
+fn split_into_tuple(text: &str, separator: char) -> Option<(&str, &str)> {
+    // Split a string into a tuple of strings without the separator
+    match text.find(separator) {
+        None => None,
+        Some(i) => {
+            let (left, right) = text.split_at(i);
+            Some((left, &right[1..]))
+        }
+    }
+}
+
+fn partition_one<F: Fn(&Digit) -> bool>(
+    haystack: Vec<Digit>,
+    predicate: F,
+) -> Result<(Digit, Vec<Digit>), Day08Error> {
+    // Find an element in a vector by a predicate, return the item and the vector without the item
+    let i = haystack
+        .iter()
+        .position(predicate)
+        .ok_or(Day08Error::InvalidInput)?;
+    let mut haystack = haystack;
+    let one = haystack.remove(i);
+    Ok((one, haystack))
+}
+
+fn extra_segment(a: &Digit, b: &Digit) -> Result<char, Day08Error> {
+    // Get the segment that is in a but not b as an owned char
+    Ok((a - b)
+        .iter()
+        .next()
+        .ok_or(Day08Error::InvalidInput)?
+        .to_owned())
+}
+
+fn part_one((_left, right): &Entry) -> Result<usize, Day08Error> {
+    // Mapping from number of segments in a digit to the digit's numeric value
+    // where the this is unique
+    let segment_number_to_unique_digit = HashMap::from([(2, 1), (4, 4), (3, 7), (7, 8)]);
+
+    // In part one, simply count the number of unique digits in the output
+    Ok(right
+        .iter()
+        .map(|s| s.len())
+        .filter_map(|x| segment_number_to_unique_digit.get(&x))
+        .count())
+}
+
+fn part_two((left, right): &Entry) -> Result<usize, Day08Error> {
+    let left: Vec<Digit> = left
+        .iter()
+        .map(|digit| HashSet::from_iter(digit.chars()))
+        .collect();
+
+    // Extract all the uniquely-sized digits
+    let (one, left) = partition_one(left, |x| x.len() == 2)?;
+    let (four, left) = partition_one(left, |x| x.len() == 4)?;
+    let (seven, left) = partition_one(left, |x| x.len() == 3)?;
+    let (eight, left) = partition_one(left, |x| x.len() == 7)?;
+
+    // Three is the only digit with 5 segments that is a superset of one
+    let (three, left) = partition_one(left, |x| x.len() == 5 && x.is_superset(&one))?;
+
+    // Nine is the only digit with 6 segments that is a superset of three
+    let (nine, left) = partition_one(left, |x| x.len() == 6 && x.is_superset(&three))?;
+    // There are two digits with 6 segments remaining: zero and six
+    // Zero is the only one that is a superset of one
+    let (zero, left) = partition_one(left, |x| x.len() == 6 && x.is_superset(&one))?;
+    // Six is the final digit with six segments
+    let (six, left) = partition_one(left, |x| x.len() == 6)?;
+
+    // a (top segment) is the only segment present in seven but not one
+    let a = extra_segment(&seven, &one)?;
+    // e (lower left) is the only segment present in eight but not nine
+    let _e = extra_segment(&eight, &nine)?;
+    // c (upper right) is the only segment present in one but not six
+    let c = extra_segment(&one, &six)?;
+    // f (lower right) is the other segment in one
+    let _f = one.iter().find(|x| x != &&c).ok_or(Day08Error::InvalidInput)?;
+    // d (middle) is the only segment in eight and not zero
+    let d = extra_segment(&eight, &zero)?;
+
+    // There are two digits left: 2 and 5
+    // Two is the one with a c-segment
+    let (two, left) = partition_one(left, |x| x.contains(&c))?;
+    let (five, _) = partition_one(left, |_| true)?;
+
+    let e = extra_segment(&six, &five)?;
+    let _g = extra_segment(&two, &HashSet::from([a, c, d, e]))?;
+
+    // Get a mapping from segments to numeric values
+    // Use sorted strings as keys because sets don't seem to play nice
+    let map = [zero, one, two, three, four, five, six, seven, eight, nine]
+        .iter()
+        .enumerate()
+        .map(|(i, x)| (x.iter().sorted().collect::<String>(), i))
+        .collect::<HashMap<String, usize>>();
+
+    // Get the numeric value corresponding to each number in right
+    Ok(right
+        .iter()
+        .map(|x| {
+            map.get(&x.chars().sorted().collect::<String>())
+                .ok_or(Day08Error::InvalidInput)
+        })
+        .collect::<Result<Vec<&usize>, Day08Error>>()?
+        // Convert digits to base 10 number
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, x)| *x * 10_usize.pow(i as u32))
+        .sum::<usize>())
+}
+
+pub struct Day08;
+
+impl Solution for Day08 {
+    type Parsed = Vec<Entry>;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AdventError> {
+        input
+            .lines()
+            .map(|line| {
+                let (left, right) =
+                    split_into_tuple(line, '|').ok_or(Day08Error::InvalidInput)?;
+
+                let [left, right] = [left, right].map(|part| {
+                    part.trim()
+                        .split(' ')
+                        .map(|x| x.to_string())
+                        .collect::<Vec<_>>()
+                });
+
+                Ok((left, right))
+            })
+            .collect::<Result<Vec<Entry>, Day08Error>>()
+            .map_err(AdventError::from)
+    }
+
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<String, AdventError> {
+        let total: usize = parsed
+            .iter()
+            .map(part_one)
+            .collect::<Result<Vec<_>, Day08Error>>()?
+            .iter()
+            .sum();
+        Ok(total.to_string())
+    }
+
+    fn part_two(&self, parsed: &Self::Parsed) -> Result<String, AdventError> {
+        let total: usize = parsed
+            .iter()
+            .map(part_two)
+            .collect::<Result<Vec<_>, Day08Error>>()?
+            .iter()
+            .sum();
+        Ok(total.to_string())
+    }
+}
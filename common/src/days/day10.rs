@@ -0,0 +1,148 @@
+//! Day 10: Syntax Scoring.
+
+use crate::{AdventError, Solution};
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet, VecDeque};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Day10Error {
+    #[error("Invalid character `{c}' found in input.")]
+    InvalidChar { c: char },
+
+    #[error("Closing brace `{c}' found before any opening brace.")]
+    ClosingBeforeOpening { c: char },
+}
+
+impl From<Day10Error> for AdventError {
+    fn from(err: Day10Error) -> Self {
+        AdventError::Solve(err.to_string())
+    }
+}
+
+pub enum LineResult {
+    Corrupt(char),
+    Incomplete(VecDeque<char>),
+    Ok,
+}
+
+/// A balanced-delimiter validator for an arbitrary open/close table, e.g.
+/// `[('(', ')'), ('[', ']')]`. Built once from that table plus the per-char
+/// scores for each of the two puzzle scoring schemes, so the bracket grammar
+/// and its scoring can be swapped out without touching `parse`.
+pub struct Matcher {
+    opens: HashMap<char, char>,
+    closes: HashSet<char>,
+    corrupt_scores: HashMap<char, usize>,
+    incomplete_scores: HashMap<char, usize>,
+}
+
+impl Matcher {
+    pub fn new(
+        pairs: &[(char, char)],
+        corrupt_scores: HashMap<char, usize>,
+        incomplete_scores: HashMap<char, usize>,
+    ) -> Self {
+        Matcher {
+            opens: pairs.iter().copied().collect(),
+            closes: pairs.iter().map(|(_, close)| *close).collect(),
+            corrupt_scores,
+            incomplete_scores,
+        }
+    }
+
+    pub fn parse(&self, line: &str) -> Result<LineResult, Day10Error> {
+        let mut expect = VecDeque::<char>::new();
+        for c in line.chars() {
+            if let Some(closing) = self.opens.get(&c) {
+                expect.push_front(*closing);
+            } else if self.closes.contains(&c) {
+                let closing = expect
+                    .pop_front()
+                    .ok_or(Day10Error::ClosingBeforeOpening { c })?;
+                if closing != c {
+                    return Ok(LineResult::Corrupt(c));
+                }
+            } else {
+                return Err(Day10Error::InvalidChar { c });
+            }
+        }
+        if expect.is_empty() {
+            Ok(LineResult::Ok)
+        } else {
+            Ok(LineResult::Incomplete(expect))
+        }
+    }
+
+    // In question one, simply sum up the corrupt chars, each of which having a different
+    // associated score
+    pub fn corrupt_score(&self, line_result: &LineResult) -> Option<usize> {
+        match line_result {
+            LineResult::Corrupt(c) => self.corrupt_scores.get(c).copied(),
+            _ => None,
+        }
+    }
+
+    // In part two, multiply the previous score by 5, then add a different amount for each
+    // missing closing brace
+    pub fn incomplete_score(&self, line_result: &LineResult) -> Option<usize> {
+        match line_result {
+            LineResult::Incomplete(rest) => Some(rest.iter().fold(0, |acc, c| {
+                acc * 5 + self.incomplete_scores.get(c).copied().unwrap_or(0)
+            })),
+            _ => None,
+        }
+    }
+
+    /// The string of closing characters (in popped order) that would
+    /// complete an `Incomplete` line; `None` for any other result.
+    pub fn autocomplete(&self, line_result: &LineResult) -> Option<String> {
+        match line_result {
+            LineResult::Incomplete(rest) => Some(rest.iter().collect()),
+            _ => None,
+        }
+    }
+}
+
+fn bracket_matcher() -> Matcher {
+    Matcher::new(
+        &[('[', ']'), ('{', '}'), ('<', '>'), ('(', ')')],
+        HashMap::from([(')', 3), (']', 57), ('}', 1197), ('>', 25137)]),
+        HashMap::from([(')', 1), (']', 2), ('}', 3), ('>', 4)]),
+    )
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Parsed = Vec<String>;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AdventError> {
+        Ok(input.lines().map(|l| l.to_string()).collect())
+    }
+
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<String, AdventError> {
+        let matcher = bracket_matcher();
+        let score: usize = parsed
+            .iter()
+            .map(|line| matcher.parse(line))
+            .collect::<Result<Vec<LineResult>, Day10Error>>()?
+            .iter()
+            .filter_map(|result| matcher.corrupt_score(result))
+            .sum();
+        Ok(score.to_string())
+    }
+
+    fn part_two(&self, parsed: &Self::Parsed) -> Result<String, AdventError> {
+        let matcher = bracket_matcher();
+        let mut score: Vec<usize> = parsed
+            .iter()
+            .map(|line| matcher.parse(line))
+            .collect::<Result<Vec<LineResult>, Day10Error>>()?
+            .iter()
+            .filter_map(|result| matcher.incomplete_score(result))
+            .collect();
+        score.sort_unstable();
+        Ok(score[score.len() / 2].to_string())
+    }
+}
@@ -0,0 +1,98 @@
+//! Day 11: Dumbo Octopus.
+
+use crate::grid::{Coord, Grid, Mode};
+use crate::{AdventError, Solution};
+use std::collections::HashSet;
+
+type Flashed = HashSet<Coord<2>>;
+
+fn flash(pos: Coord<2>, grid: &mut Grid<u32, 2>, flashed: &Flashed) -> Flashed {
+    if flashed.contains(&pos) {
+        return Flashed::new();
+    }
+
+    let mut flashed = flashed.clone();
+    flashed.insert(pos);
+
+    for neighbour in grid.diagonal_neighbours(pos) {
+        let Some(v) = grid.get_mut(neighbour) else {
+            continue;
+        };
+        *v += 1;
+
+        if *v > 9 && !flashed.contains(&neighbour) {
+            flashed = &flashed | &flash(neighbour, grid, &flashed);
+        }
+    }
+
+    flashed
+}
+
+fn step(grid: &mut Grid<u32, 2>) -> usize {
+    // Increment entire grid
+    for pos in grid.coords().collect::<Vec<_>>() {
+        grid[pos] += 1;
+    }
+
+    // Flash every octopus now above 9, cascading into its neighbours
+    let mut flashed = Flashed::new();
+    for pos in grid.coords().collect::<Vec<_>>() {
+        if grid[pos] > 9 && !flashed.contains(&pos) {
+            flashed = &flashed | &flash(pos, grid, &flashed);
+        }
+    }
+
+    // Everything that flashed resets to zero
+    for pos in &flashed {
+        grid[*pos] = 0;
+    }
+
+    flashed.len()
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Parsed = Grid<u32, 2>;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AdventError> {
+        let rows: Vec<Vec<u32>> = input
+            .lines()
+            .map(|line| {
+                line.chars()
+                    .map(|c| c.to_digit(10).ok_or_else(|| AdventError::Solve(format!("Could not parse char `{c}' to numeric digit."))))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let height = rows.len();
+        let width = rows[0].len();
+
+        let mut grid = Grid::new([width, height], 0, Mode::Bounded);
+        for (y, row) in rows.into_iter().enumerate() {
+            for (x, v) in row.into_iter().enumerate() {
+                grid[[x as isize, y as isize]] = v;
+            }
+        }
+
+        Ok(grid)
+    }
+
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<String, AdventError> {
+        let mut grid = parsed.clone();
+        let flashes: usize = (0..100).map(|_| step(&mut grid)).sum();
+        Ok(flashes.to_string())
+    }
+
+    fn part_two(&self, parsed: &Self::Parsed) -> Result<String, AdventError> {
+        let mut grid = parsed.clone();
+        let total = grid.sizes().iter().product();
+        let mut steps = 0;
+        loop {
+            steps += 1;
+            if step(&mut grid) == total {
+                return Ok(steps.to_string());
+            }
+        }
+    }
+}
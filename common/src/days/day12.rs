@@ -0,0 +1,184 @@
+//! Day 12: Passage Pathing.
+
+use crate::graph::Graph;
+use crate::{AdventError, QuestionPart, Solution};
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+use thiserror::Error;
+
+#[derive(Eq, PartialEq, Hash, Clone, Debug)]
+pub enum Vertex {
+    Start,
+    End,
+    Small(String),
+    Big(String),
+}
+
+#[derive(Error, Debug)]
+enum Day12Error {
+    #[error("Invalid input")]
+    InvalidInput,
+
+    #[error("Invalid vertex `{x}'. Expected `start', `end', or a sequence of all-uppercase or all-lowercase letters.")]
+    InvalidVertex { x: String },
+
+    #[error("No start vertex in input")]
+    NoStart,
+}
+
+impl From<Day12Error> for AdventError {
+    fn from(err: Day12Error) -> Self {
+        AdventError::Solve(err.to_string())
+    }
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Parsed = Graph<Vertex>;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AdventError> {
+        let lines = input.lines().collect::<Vec<_>>();
+        let lines = lines
+            .iter()
+            .map(|l| l.split('-').collect_tuple::<(&str, &str)>().ok_or(Day12Error::InvalidInput))
+            .collect::<Result<Vec<_>, Day12Error>>()?;
+
+        // Parse vertices of strings into enum variants
+        let edges = lines
+            .iter()
+            .map(|(a, b)| {
+                [a, b]
+                    .iter()
+                    .map(|x| match **x {
+                        "start" => Ok(Vertex::Start),
+                        "end" => Ok(Vertex::End),
+                        _ => {
+                            if x.chars().all(|c| ('a'..='z').contains(&c)) {
+                                Ok(Vertex::Small(x.to_string()))
+                            } else if x.chars().all(|c| ('A'..='Z').contains(&c)) {
+                                Ok(Vertex::Big(x.to_string()))
+                            } else {
+                                Err(Day12Error::InvalidVertex { x: x.to_string() })
+                            }
+                        }
+                    })
+                    .collect_tuple()
+                    .ok_or(Day12Error::InvalidInput)
+            })
+            .collect::<Result<Vec<_>, Day12Error>>()?;
+
+        let mut graph = Graph::new();
+        for (a, b) in edges {
+            // Could not find a way to collect_tuple into Result<tuple, Error>, so do it here
+            let a = a?;
+            let b = b?;
+            graph.insert_undirected(a, b);
+        }
+
+        if !graph.contains(&Vertex::Start) {
+            return Err(Day12Error::NoStart.into());
+        }
+
+        Ok(graph)
+    }
+
+    fn part_one(&self, graph: &Self::Parsed) -> Result<String, AdventError> {
+        Ok(count_paths_from_start(graph, QuestionPart::One).to_string())
+    }
+
+    fn part_two(&self, graph: &Self::Parsed) -> Result<String, AdventError> {
+        Ok(count_paths_from_start(graph, QuestionPart::Two).to_string())
+    }
+}
+
+// Whether some small cave already appears twice in `path`, i.e. part two's one allowed
+// small-cave revisit has already been spent.
+pub fn already_doubled_small_cave(path: &[Vertex]) -> bool {
+    let mut seen = HashSet::new();
+    path.iter()
+        .filter(|v| matches!(v, Vertex::Small(_)))
+        .any(|v| !seen.insert(v))
+}
+
+// Count completions from `v` to `Vertex::End` without ever materializing a path, so part two's
+// "revisit one small cave" search stays linear-ish instead of exponential in both time and
+// memory. `visited` is a bitmask over the index `small_indices` assigns to each small cave, and
+// `doubled` tracks whether the one allowed small-cave revisit has already been spent. Memoized on
+// exactly the triple the result depends on: `(v, visited, doubled)`.
+fn count_paths(
+    v: &Vertex,
+    graph: &Graph<Vertex>,
+    small_indices: &HashMap<Vertex, u64>,
+    visited: u64,
+    doubled: bool,
+    memo: &mut HashMap<(Vertex, u64, bool), usize>,
+) -> usize {
+    match v {
+        Vertex::End => return 1,
+        // Can only be reached here on a revisit (the initial call starts from start's neighbors),
+        // and start may never be revisited
+        Vertex::Start => return 0,
+        _ => {}
+    }
+
+    let key = (v.to_owned(), visited, doubled);
+    if let Some(&count) = memo.get(&key) {
+        return count;
+    }
+
+    let (visited, doubled) = match v {
+        Vertex::Small(_) => {
+            let bit = small_indices[v];
+            if visited & bit != 0 {
+                if doubled {
+                    return 0;
+                }
+                (visited, true)
+            } else {
+                (visited | bit, doubled)
+            }
+        }
+        _ => (visited, doubled),
+    };
+
+    // Big caves carry the mask through untouched: they can always be revisited
+    let count = graph
+        .neighbours(v)
+        .map(|w| count_paths(w, graph, small_indices, visited, doubled, memo))
+        .sum();
+
+    memo.insert(key, count);
+    count
+}
+
+fn count_paths_from_start(graph: &Graph<Vertex>, question_part: QuestionPart) -> usize {
+    // Hacky way to implement the question part: if we're part one, just tell the search that
+    // we've already visited our one allowed small cave twice
+    let already_doubled = matches!(question_part, QuestionPart::One);
+
+    let small_indices: HashMap<Vertex, u64> = graph
+        .nodes()
+        .filter(|v| matches!(v, Vertex::Small(_)))
+        .enumerate()
+        .map(|(i, v)| (v.to_owned(), 1 << i))
+        .collect();
+
+    let mut memo = HashMap::new();
+    graph
+        .neighbours(&Vertex::Start)
+        .map(|w| count_paths(w, graph, &small_indices, 0, already_doubled, &mut memo))
+        .sum()
+}
+
+// Print a path like in the website, separated by comma
+pub fn print_path(path: &[Vertex]) -> String {
+    path.iter()
+        .map(|v| match v {
+            Vertex::Start => "start",
+            Vertex::End => "end",
+            Vertex::Small(c) => c,
+            Vertex::Big(c) => c,
+        })
+        .join(",")
+}
@@ -0,0 +1,124 @@
+//! Day 15: Chiton.
+
+use crate::pathfind::{a_star, manhattan};
+use crate::{AdventError, Solution};
+use itertools::iproduct;
+use std::collections::HashMap;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+enum Day15Error {
+    #[error("Could not parse char `{c}' to numeric digit.")]
+    Parse { c: char },
+}
+
+impl From<Day15Error> for AdventError {
+    fn from(err: Day15Error) -> Self {
+        AdventError::Solve(err.to_string())
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Copy, Debug)]
+struct Point {
+    x: usize,
+    y: usize,
+}
+
+impl Point {
+    fn new(x: usize, y: usize) -> Point {
+        Point { x, y }
+    }
+    fn left(self) -> Option<Point> {
+        if self.x > 0 {
+            Some(Point::new(self.x - 1, self.y))
+        } else {
+            None
+        }
+    }
+    fn top(self) -> Option<Point> {
+        if self.y > 0 {
+            Some(Point::new(self.x, self.y - 1))
+        } else {
+            None
+        }
+    }
+    fn right(self) -> Option<Point> {
+        Some(Point::new(self.x + 1, self.y))
+    }
+    fn bottom(self) -> Option<Point> {
+        Some(Point::new(self.x, self.y + 1))
+    }
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Parsed = (usize, usize, HashMap<Point, u32>);
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AdventError> {
+        let lines: Vec<&str> = input.lines().collect();
+        let nrows = lines.len();
+        let ncols = lines[0].len();
+        let weights = lines
+            .iter()
+            .enumerate()
+            .flat_map(|(y, l)| {
+                l.chars().enumerate().map(move |(x, c)| {
+                    Ok((
+                        Point::new(x, y),
+                        c.to_digit(10).ok_or(Day15Error::Parse { c })?,
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>, Day15Error>>()?;
+
+        Ok((ncols, nrows, HashMap::<Point, u32>::from_iter(weights)))
+    }
+
+    fn part_one(&self, (ncols, nrows, weights): &Self::Parsed) -> Result<String, AdventError> {
+        Ok(lowest_risk(*ncols, *nrows, weights).to_string())
+    }
+
+    fn part_two(&self, (ncols, nrows, weights): &Self::Parsed) -> Result<String, AdventError> {
+        let (ncols, nrows) = (*ncols, *nrows);
+        let weights = HashMap::<Point, u32>::from_iter(iproduct!(0..5 * ncols, 0..5 * nrows).map(
+            |(x, y)| {
+                let point = Point { x, y };
+                let region = Point {
+                    x: x / ncols,
+                    y: y / nrows,
+                };
+                (
+                    point,
+                    (weights.get(&Point::new(x % ncols, y % nrows)).unwrap()
+                        + (region.x + region.y) as u32
+                        - 1)
+                        % 9
+                        + 1,
+                )
+            },
+        ));
+        Ok(lowest_risk(ncols * 5, nrows * 5, &weights).to_string())
+    }
+}
+
+fn lowest_risk(ncols: usize, nrows: usize, weights: &HashMap<Point, u32>) -> u32 {
+    let source = Point::new(0, 0);
+    let target = Point::new(ncols - 1, nrows - 1);
+
+    let neighbours = |point: &Point| {
+        [point.top(), point.left(), point.right(), point.bottom()]
+            .into_iter()
+            .flatten()
+            .filter(|p| p.x < ncols && p.y < nrows)
+            .collect::<Vec<_>>()
+    };
+    let weight = |point: &Point| *weights.get(point).unwrap();
+    let heuristic = |point: &Point| manhattan((point.x, point.y), (target.x, target.y));
+
+    // The source point is never counted, but a_star's g already excludes it
+    // since weight() is only ever charged for stepping *into* a point.
+    a_star(source, target, neighbours, weight, heuristic)
+        .expect("target is always reachable on a fully-connected grid")
+        .0
+}
@@ -0,0 +1,813 @@
+//! Day 23: Amphipod.
+
+use crate::{AdventError as CommonError, QuestionPart, Solution};
+use lazy_static::lazy_static;
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete::{char, line_ending};
+use nom::combinator::value;
+use nom::multi::{many1, separated_list1};
+use nom::sequence::{delimited, preceded};
+use nom::IResult;
+use priority_queue::PriorityQueue;
+use regex::Regex;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::mem::discriminant;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+enum Day23Error {
+    #[error("Could not find a solution for the given input.")]
+    NoSolution,
+
+    #[error("Invalid input at line {line}, column {col}: expected {expected}.")]
+    ParseFailure {
+        offset: usize,
+        line: usize,
+        col: usize,
+        expected: &'static str,
+    },
+}
+
+impl From<Day23Error> for CommonError {
+    fn from(err: Day23Error) -> Self {
+        CommonError::Solve(err.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Hash, Eq)]
+pub enum Amphipod {
+    Amber,
+    Bronze,
+    Copper,
+    Desert,
+}
+
+impl Amphipod {
+    pub fn weight(&self) -> usize {
+        // Cost to move different amphipod types one spot
+        match self {
+            Self::Amber => 1,
+            Self::Bronze => 10,
+            Self::Copper => 100,
+            Self::Desert => 1000,
+        }
+    }
+}
+
+// The shape of a maze: how many rooms it has and how many slots deep each room is. Everything
+// below derives its geometry (room membership, hallway roots, wait spots, state length) from this
+// arithmetically instead of matching against literal indices, so a maze with a different depth
+// just works. The one dimension that stays fixed is `num_rooms`, since it is tied 1:1 to the four
+// `Amphipod` variants (one room per amphipod type) -- a maze with a different *room count* would
+// need new `Amphipod` variants too, which is out of scope here.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Puzzle {
+    num_rooms: usize,
+    depth: usize,
+}
+
+impl Puzzle {
+    const fn classic(depth: usize) -> Self {
+        Self { num_rooms: 4, depth }
+    }
+
+    const fn hallway_length(&self) -> usize {
+        2 * self.num_rooms + 3
+    }
+
+    const fn state_length(&self) -> usize {
+        self.hallway_length() + self.num_rooms * self.depth
+    }
+
+    // Indices of the room at `room_index` (0-based, left to right), top to bottom
+    fn room(&self, room_index: usize) -> Vec<usize> {
+        (0..self.depth)
+            .map(|row| self.hallway_length() + row * self.num_rooms + room_index)
+            .collect()
+    }
+
+    // Indices of the valid spots an amphipod can wait: every hallway cell except the ones
+    // directly outside a room
+    fn wait_spots(&self) -> Vec<usize> {
+        let roots: Vec<usize> = (1..=self.num_rooms).map(|room| 2 * room).collect();
+        (0..self.hallway_length()).filter(|i| !roots.contains(i)).collect()
+    }
+}
+
+// The full (deepest, widest) shape this puzzle ever takes, used to size the fixed-capacity
+// `State` array and the `ROUTES` table below. A real maze only ever uses a prefix of it: part one
+// leaves the last two rows of each room empty, and `room`/`root`/`Amphipod::room` are always
+// called with the `Puzzle` actually being solved, not this one.
+const FULL_PUZZLE: Puzzle = Puzzle::classic(4);
+const FULL_STATE_LENGTH: usize = FULL_PUZZLE.state_length();
+pub type State = [Option<Amphipod>; FULL_STATE_LENGTH];
+
+// Totally empty state
+// Used for tests
+#[cfg(test)]
+const EMPTY_STATE: State = [None; FULL_STATE_LENGTH];
+
+impl Amphipod {
+    // Which room (0-based, left to right) this amphipod type belongs in, e.g. room 0 for Amber.
+    // Assumes one room per `Amphipod` variant, in enum-declaration order.
+    fn index(&self) -> usize {
+        match self {
+            Self::Amber => 0,
+            Self::Bronze => 1,
+            Self::Copper => 2,
+            Self::Desert => 3,
+        }
+    }
+
+    fn room(&self, puzzle: &Puzzle) -> Vec<usize> {
+        // Indices of the room designated to a given amphipod type
+        puzzle.room(self.index())
+    }
+}
+
+fn room(i: usize, puzzle: &Puzzle) -> Option<Vec<usize>> {
+    // Similar to the above, but gets the rest of the room given any index
+    if i < puzzle.hallway_length() {
+        return None;
+    }
+    let offset = i - puzzle.hallway_length();
+    if offset >= puzzle.num_rooms * puzzle.depth {
+        return None;
+    }
+    Some(puzzle.room(offset % puzzle.num_rooms))
+}
+
+fn root(i: usize, puzzle: &Puzzle) -> usize {
+    // Get the root of a room given any of its indices. The root is the hallway cell directly
+    // outside the room
+    if i < puzzle.hallway_length() {
+        return i;
+    }
+    let room_index = (i - puzzle.hallway_length()) % puzzle.num_rooms;
+    2 * (room_index + 1)
+}
+
+fn variant_eq<T>(a: &T, b: &T) -> bool {
+    // Check if two enums have the same variant
+    discriminant(a) == discriminant(b)
+}
+
+// Print a state in the format of the problem description and input
+pub fn print(state: &State, puzzle: &Puzzle) {
+    let state = state[..puzzle.state_length()]
+        .iter()
+        .map(|x| match x {
+            Some(Amphipod::Amber) => "A",
+            Some(Amphipod::Bronze) => "B",
+            Some(Amphipod::Copper) => "C",
+            Some(Amphipod::Desert) => "D",
+            None => ".",
+        })
+        .collect::<Vec<_>>();
+    println!("{}", "#".repeat(puzzle.hallway_length() + 2));
+    let (left, right) = state.split_at(puzzle.hallway_length());
+    println!("#{}#", left.join(""));
+    let mut chunks = right.chunks(puzzle.num_rooms);
+    println!("###{}###", chunks.next().unwrap().join("#"));
+    for chunk in chunks {
+        println!("  #{}#", chunk.join("#"));
+    }
+    println!("  {}", "#".repeat(puzzle.num_rooms * 2 + 1));
+}
+
+fn parse_part(input: &str) -> Vec<Option<Amphipod>> {
+    // Extract all the "ABCD." from a string into a vec of amphipods
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r"[A-D\.]").unwrap();
+    }
+
+    RE.find_iter(input)
+        .into_iter()
+        .map(|m| m.as_str())
+        .map(|x| match x {
+            "A" => Some(Amphipod::Amber),
+            "B" => Some(Amphipod::Bronze),
+            "C" => Some(Amphipod::Copper),
+            "D" => Some(Amphipod::Desert),
+            "." => None,
+            // If the regex matches, this should be all the possible states
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+fn parse(input: &str, question_part: QuestionPart) -> (State, Puzzle) {
+    // Parse the input into a `State` and the `Puzzle` describing its shape. This is a "dumb"
+    // function that does no validation, relying on the regex matching in `parse_maze`/`build_state`
+    // for that. It is kept separate so tests can quickly build states for ad-hoc mazes.
+    let flat = parse_part(input);
+
+    // How many room rows the input actually gives, derived arithmetically from the flattened
+    // length rather than matched against the two literal sizes (19, 27) this used to be
+    let raw_depth = (flat.len() - FULL_PUZZLE.hallway_length()) / FULL_PUZZLE.num_rooms;
+
+    let flat = match (question_part, raw_depth) {
+        // Part two folds in the two constant rows given in the problem statement, between the
+        // first and last given rows -- but only when handed the bare 2-row input. A maze that
+        // already supplies every row (tests, or a hand-built variant) is used as-is
+        (QuestionPart::Two, 2) => {
+            let split = FULL_PUZZLE.hallway_length() + FULL_PUZZLE.num_rooms;
+            [&flat[..split], &parse_part("DCBADBAC"), &flat[split..]].concat()
+        }
+        _ => flat,
+    };
+
+    let depth = (flat.len() - FULL_PUZZLE.hallway_length()) / FULL_PUZZLE.num_rooms;
+    let puzzle = Puzzle::classic(depth);
+
+    let mut state = [None; FULL_STATE_LENGTH];
+    state[..flat.len()].copy_from_slice(&flat);
+    (state, puzzle)
+}
+
+// The grammar below mirrors the shape the problem statement always hands us, e.g.:
+//
+//   #############
+//   #...........#
+//   ###D#A#A#D###
+//     #C#C#B#B#
+//     #########
+//
+// Each row function consumes exactly one line's worth of structure (no line endings), so the
+// caller can report exactly which line/column parsing broke down at.
+
+fn cell(input: &str) -> IResult<&str, Option<Amphipod>> {
+    alt((
+        value(Some(Amphipod::Amber), char('A')),
+        value(Some(Amphipod::Bronze), char('B')),
+        value(Some(Amphipod::Copper), char('C')),
+        value(Some(Amphipod::Desert), char('D')),
+        value(None, char('.')),
+    ))(input)
+}
+
+fn top_border(input: &str) -> IResult<&str, ()> {
+    value((), many1(char('#')))(input)
+}
+
+fn bottom_border(input: &str) -> IResult<&str, ()> {
+    preceded(tag("  "), value((), many1(char('#'))))(input)
+}
+
+fn hallway_row(input: &str) -> IResult<&str, Vec<Option<Amphipod>>> {
+    delimited(char('#'), many1(cell), char('#'))(input)
+}
+
+fn first_room_row(input: &str) -> IResult<&str, Vec<Option<Amphipod>>> {
+    delimited(tag("###"), separated_list1(char('#'), cell), tag("###"))(input)
+}
+
+fn room_row(input: &str) -> IResult<&str, Vec<Option<Amphipod>>> {
+    preceded(tag("  "), delimited(char('#'), separated_list1(char('#'), cell), char('#')))(input)
+}
+
+// Run one grammar stage, converting a nom failure into the byte offset its deepest attempt got
+// stuck at, tagged with a human label for what was expected there. Sequencing the rows by hand
+// like this (rather than with a single `tuple`/`map`) is what lets each stage report its own
+// label instead of one generic message for the whole maze.
+fn stage<'a, O>(
+    input: &'a str,
+    expected: &'static str,
+    result: IResult<&'a str, O>,
+) -> Result<(&'a str, O), Day23Error> {
+    result.map_err(|err| {
+        let remaining = match err {
+            nom::Err::Error(e) | nom::Err::Failure(e) => e.input,
+            nom::Err::Incomplete(_) => "",
+        };
+        let offset = input.len() - remaining.len();
+        let consumed = &input[..offset];
+        let line = consumed.matches('\n').count() + 1;
+        let col = offset - consumed.rfind('\n').map_or(0, |i| i + 1) + 1;
+        Day23Error::ParseFailure { offset, line, col, expected }
+    })
+}
+
+// Parse a full maze (border, hallway, and however many room rows are present) straight into its
+// rows, reporting exactly where and why parsing failed instead of just "doesn't match". This is
+// deliberately part-independent: folding in part two's extra rows happens later, in `build_state`.
+fn parse_maze(input: &str) -> Result<(Vec<Option<Amphipod>>, Vec<Vec<Option<Amphipod>>>), Day23Error> {
+    let (rest, _) = stage(input, "the top border (`#############`)", top_border(input))?;
+    let (rest, _) = stage(input, "a line ending", line_ending(rest))?;
+    let (rest, hallway) = stage(input, "the hallway row (`#...........#`)", hallway_row(rest))?;
+    let (rest, _) = stage(input, "a line ending", line_ending(rest))?;
+    let (rest, first_row) = stage(input, "the first room row (`###A#B#C#D###`)", first_room_row(rest))?;
+    let (rest, _) = stage(input, "a line ending", line_ending(rest))?;
+    let (rest, mut rows) = stage(
+        input,
+        "a room row (`  #A#B#C#D#`)",
+        separated_list1(line_ending, room_row)(rest),
+    )?;
+    let (rest, _) = stage(input, "a line ending", line_ending(rest))?;
+    stage(input, "the bottom border (`  #########`)", bottom_border(rest))?;
+
+    rows.insert(0, first_row);
+    Ok((hallway, rows))
+}
+
+// Fold in part two's two constant rows (given in the problem statement, between the first and
+// last given rows -- but only when handed the bare 2-row input; a maze that already supplies
+// every row, e.g. in tests, is used as-is) and build the `State`/`Puzzle` the search needs.
+pub fn build_state(
+    (hallway, rows): &(Vec<Option<Amphipod>>, Vec<Vec<Option<Amphipod>>>),
+    question_part: QuestionPart,
+) -> (State, Puzzle) {
+    let mut rows = rows.clone();
+    if matches!(question_part, QuestionPart::Two) && rows.len() == 2 {
+        let bottom = rows.pop().unwrap();
+        rows.push(parse_part("DCBA"));
+        rows.push(parse_part("DBAC"));
+        rows.push(bottom);
+    }
+
+    let puzzle = Puzzle::classic(rows.len());
+    let mut state = [None; FULL_STATE_LENGTH];
+    state[..hallway.len()].copy_from_slice(hallway);
+    for (row_index, row) in rows.into_iter().enumerate() {
+        let start = hallway.len() + row_index * row.len();
+        state[start..start + row.len()].copy_from_slice(&row);
+    }
+
+    (state, puzzle)
+}
+
+fn room_has_bad_guys(amphipod: Amphipod, state: State, puzzle: &Puzzle) -> bool {
+    // Check if a room has "bad guys" (any other type of amphipod)
+    amphipod
+        .room(puzzle)
+        .into_iter()
+        .filter_map(|i| state[i])
+        .any(|other| !variant_eq(&other, &amphipod))
+}
+
+fn low_high(a: usize, b: usize) -> (usize, usize) {
+    if a < b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+// The cost to rise from `i` up to the hallway: the number of rooms it has to clear, or 0 if `i`
+// is already in the hallway. Shared by `cost_to_hallway`, `compute_route`, and `heuristic`, which
+// otherwise would each reimplement the same room-index arithmetic.
+fn rise(i: usize, puzzle: &Puzzle) -> usize {
+    match room(i, puzzle) {
+        None => 0,
+        Some(_) => (i - puzzle.hallway_length()) / puzzle.num_rooms + 1,
+    }
+}
+
+fn cost_to_hallway(state: &State, from: usize, puzzle: &Puzzle) -> Option<usize> {
+    // Calculate the cost to get from `from` index to hallway (root), or None if unreachable
+    match room(from, puzzle) {
+        // If not in room, we're already there and cost is zero
+        None => Some(0),
+        // If we're in a room, and we're being blocked, return None
+        Some(room)
+            if room
+                .iter()
+                .filter(|i| i < &&from)
+                .any(|x| state[*x].is_some()) =>
+        {
+            None
+        }
+        // Otherwise, it's the index inside the rooms divided (and floored) by the number of rooms
+        _ => Some(rise(from, puzzle)),
+    }
+}
+
+#[test]
+fn test_cost_to_hallway() {
+    assert!(cost_to_hallway(&EMPTY_STATE, 15, &Puzzle::classic(2)).is_some());
+}
+
+// A fixed step count plus the ordered list of intermediate cells that must be empty for a move
+// from `from` to `to` to be legal. Both are pure geometry (hallway/room layout), independent of
+// occupancy, so they only need to be computed once per `(from, to)` pair; see `ROUTES`.
+#[derive(Debug, Clone)]
+struct Route {
+    cost: usize,
+    path: Vec<usize>,
+}
+
+fn compute_route(from: usize, to: usize) -> Option<Route> {
+    if from == to {
+        return None;
+    }
+
+    // Geometry doesn't depend on which part is being solved: a room cell belongs to the same room
+    // and sits at the same depth either way, so always consult the full (deepest) room layout here.
+    let room_from = room(from, &FULL_PUZZLE);
+    let room_to = room(to, &FULL_PUZZLE);
+
+    // If `from` and `to` are in the same room, the path is a straight line up/down it
+    if room_from.as_ref().map_or(false, |r| r.contains(&to)) {
+        let (low, high) = low_high(from, to);
+        let path = room_from
+            .unwrap()
+            .into_iter()
+            .filter(|i| *i > low && *i < high)
+            .collect();
+        return Some(Route {
+            cost: (high - low) / FULL_PUZZLE.num_rooms,
+            path,
+        });
+    }
+
+    // Otherwise the path rises out of `from`'s room (if any), crosses the hallway, then descends
+    // into `to`'s room (if any)
+    let (hallway_low, hallway_high) = low_high(root(from, &FULL_PUZZLE), root(to, &FULL_PUZZLE));
+    let mut path: Vec<usize> = room_from
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|i| *i < from)
+        .rev()
+        .collect();
+    path.extend((hallway_low..=hallway_high).filter(|i| *i != from && *i != to));
+    path.extend(room_to.unwrap_or_default().into_iter().filter(|i| *i < to));
+
+    Some(Route {
+        cost: rise(from, &FULL_PUZZLE) + (hallway_high - hallway_low) + rise(to, &FULL_PUZZLE),
+        path,
+    })
+}
+
+lazy_static! {
+    // Every pair's fixed cost and required-empty path, computed once at startup instead of on
+    // every `reachable` call. Sized for `FULL_PUZZLE`, the deepest shape this maze ever takes;
+    // a shallower puzzle (part one) just never populates or queries the unused tail of indices.
+    static ref ROUTES: [[Option<Route>; FULL_STATE_LENGTH]; FULL_STATE_LENGTH] = {
+        let mut routes: [[Option<Route>; FULL_STATE_LENGTH]; FULL_STATE_LENGTH] =
+            std::array::from_fn(|_| std::array::from_fn(|_| None));
+        for from in 0..FULL_STATE_LENGTH {
+            for to in 0..FULL_STATE_LENGTH {
+                routes[from][to] = compute_route(from, to);
+            }
+        }
+        routes
+    };
+}
+
+fn reachable(state: &State, from: usize, to: usize) -> Option<usize> {
+    // If `to` is reachable from `from`, return the cost, or None otherwise. The step count and the
+    // cells that must be empty come straight out of the precomputed `ROUTES` table; only the
+    // occupancy check itself is done per call.
+    let route = ROUTES[from][to].as_ref()?;
+    if route.path.iter().any(|&i| state[i].is_some()) || state[to].is_some() {
+        None
+    } else {
+        Some(route.cost)
+    }
+}
+
+#[test]
+fn test_reachable() {
+    assert_eq!(reachable(&EMPTY_STATE, 15, 16), Some(6));
+    assert_eq!(reachable(&EMPTY_STATE, 0, 18), Some(10));
+
+    let input = "
+        #...A.......#
+        ###.#.#.#.###
+          #.#.#.#.#";
+    assert_eq!(reachable(&parse(input, QuestionPart::One).0, 15, 16), None);
+
+    let input = "
+        #A.........B#
+        ###.#.#.#.###
+          #.#.#C#D#";
+    assert_eq!(reachable(&parse(input, QuestionPart::One).0, 0, 15), Some(4));
+
+    assert_eq!(reachable(&EMPTY_STATE, 23, 11), Some(3));
+    assert_eq!(reachable(&EMPTY_STATE, 24, 12), Some(3));
+}
+
+// Represents a move or a step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move {
+    // Index of position to move from
+    pub from: usize,
+    // Index of position to move to
+    pub to: usize,
+    // State after move
+    pub state: State,
+    // Cost of move
+    pub cost: usize,
+}
+
+fn state_swap(state: State, from: usize, to: usize) -> State {
+    // Swap the amphipod in position `from` to position `to` and set `from` to None. It is not the
+    // responsibility of this function to check that `to` is empty.
+    let mut state = state;
+    state[to] = state[from];
+    state[from] = None;
+    state
+}
+
+impl Move {
+    fn new(from: usize, to: usize, state: State, cost: usize) -> Self {
+        Self {
+            from,
+            to,
+            state: state_swap(state, from, to),
+            cost,
+        }
+    }
+
+    pub fn amphipod(&self) -> Option<Amphipod> {
+        self.state[self.to]
+    }
+}
+
+fn go_home(state: State, puzzle: &Puzzle) -> Option<Move> {
+    // Return a move for the first amphipod found that is able to go directly home
+    // or None if all are still blocked
+    state
+        .iter()
+        .enumerate()
+        // Select only spaces that have some amphipod
+        .filter_map(|(i, x)| x.map(|x| (i, x)))
+        // Filter out amphipods already in their own room
+        .filter(|(i, amphipod)| !amphipod.room(puzzle).contains(i))
+        // Filter out amphipods whose rooms have bad guys (in this case, the amphipod should not go
+        // home even if it can)
+        .filter(|(_, amphipod)| !room_has_bad_guys(*amphipod, state, puzzle))
+        // If the amphipod can get home (not blocked), return the corresponding move
+        .find_map(|(from, amphipod)| {
+            let room = amphipod.room(puzzle);
+            // Get the bottom-most empty spot in the amphipod's room
+            let to = *room.iter().rev().find(|i| state[**i].is_none()).unwrap();
+            let cost = reachable(&state, from, to)?;
+            Some(Move::new(from, to, state, cost))
+        })
+}
+
+#[test]
+fn test_go_home() {
+    let puzzle = Puzzle::classic(2);
+
+    let input = "
+        #A.........B#
+        ###.#.#.#.###
+          #.#.#C#D#";
+    let m = go_home(parse(input, QuestionPart::One).0, &puzzle).unwrap();
+    assert_eq!(m.from, 0);
+    assert_eq!(m.to, 15);
+    assert_eq!(m.cost, 4);
+
+    let input = "
+        #..........B#
+        ###.#.#.#.###
+          #A#C#C#D#";
+    let m = go_home(parse(input, QuestionPart::One).0, &puzzle).unwrap();
+    assert_eq!(m.from, 16);
+    assert_eq!(m.to, 13);
+    assert_eq!(m.cost, 5);
+
+    let input = "
+        #..........B#
+        ###.#.#C#.###
+          #A#.#C#D#";
+    let m = go_home(parse(input, QuestionPart::One).0, &puzzle).unwrap();
+    assert_eq!(m.from, 10);
+    assert_eq!(m.to, 16);
+    assert_eq!(m.cost, 8);
+}
+
+fn get_all_unblock_moves(state: State, puzzle: &Puzzle) -> Vec<Move> {
+    // Get all the currently possible unblock moves
+    state
+        .iter()
+        .enumerate()
+        // Filter out all the amphipods in the hallways
+        // Unblock moves are not moves to their home, so are not allowed from in the hallway
+        .filter(|(from, _amphipod)| from > &puzzle.hallway_length())
+        // Filter out empty spaces
+        .filter_map(|(from, amphipod)| amphipod.map(|x| (from, x)))
+        .filter(|(from, amphipod)| {
+            // Filter out amphipods that are already home
+            !amphipod.room(puzzle).contains(from)
+                // But keep them if they are blocking others
+                || room_has_bad_guys(*amphipod, state, puzzle)
+        })
+        // Add a possible move for each of the wait spots
+        // It would be more efficient to figure out what the amphipod is blocking to reduce the
+        // wait spots that make sense, but this is plenty fast
+        .map(|(from, _amphipod)| {
+            puzzle.wait_spots().into_iter().filter_map(move |to| {
+                let cost = reachable(&state, from, to)?;
+                Some(Move::new(from, to, state, cost))
+            })
+        })
+        .flatten()
+        .collect::<Vec<_>>()
+}
+
+fn is_settled(state: &State, i: usize, amphipod: Amphipod, puzzle: &Puzzle) -> bool {
+    // Whether the amphipod at `i` is already home to stay: in its own room, with nothing but its
+    // own type underneath it
+    let room = amphipod.room(puzzle);
+    room.contains(&i)
+        && room
+            .iter()
+            .filter(|j| **j > i)
+            .all(|j| state[*j].map_or(false, |other| variant_eq(&other, &amphipod)))
+}
+
+fn heuristic(state: &State, puzzle: &Puzzle) -> usize {
+    // An admissible lower bound on the remaining cost: for every amphipod not yet settled, the
+    // cost of a move that ignores collisions entirely (rise out of its room to the hallway, walk
+    // straight to its target room, step in). This never assumes more than one entry step per
+    // amphipod and ignores blocking/stacking, so it never overestimates the true remaining cost.
+    state
+        .iter()
+        .enumerate()
+        .filter_map(|(i, x)| x.map(|amphipod| (i, amphipod)))
+        .filter(|(i, amphipod)| !is_settled(state, *i, *amphipod, puzzle))
+        .map(|(i, amphipod)| {
+            let target_root = root(amphipod.room(puzzle)[0], puzzle);
+            let horizontal = root(i, puzzle).abs_diff(target_root);
+            amphipod.weight() * (rise(i, puzzle) + horizontal + 1)
+        })
+        .sum()
+}
+
+fn reconstruct_path(
+    predecessors: &HashMap<State, (State, Move)>,
+    initial_state: State,
+    target_state: State,
+) -> Vec<Move> {
+    // Walk backward from `target_state` to `initial_state` via `predecessors`, then reverse to get
+    // the moves back in the order they were made
+    let mut moves = Vec::new();
+    let mut current = target_state;
+    while current != initial_state {
+        let (previous, m) = predecessors[&current];
+        moves.push(m);
+        current = previous;
+    }
+    moves.reverse();
+    moves
+}
+
+// The solved state for `puzzle`: every amphipod in its own room, computed directly from the
+// puzzle's shape rather than written out as a literal string per depth.
+fn target_state(puzzle: &Puzzle) -> State {
+    let amphipods = [Amphipod::Amber, Amphipod::Bronze, Amphipod::Copper, Amphipod::Desert];
+    let mut state = [None; FULL_STATE_LENGTH];
+    for (room_index, amphipod) in amphipods.into_iter().enumerate() {
+        for i in puzzle.room(room_index) {
+            state[i] = Some(amphipod);
+        }
+    }
+    state
+}
+
+// Find the minimal cost to get from the initial state to the desired state. This solution is
+// basically A* over Dijkstra's algorithm: moving from one possible state to another, ordering the
+// priority queue by `g + h` (true cost so far plus the `heuristic` lower bound on what's left)
+// instead of `g` alone. Because `heuristic` never overestimates, this still finds the least costly
+// solution, but explores far fewer states than plain Dijkstra since it prefers states that are
+// actually closer to done.
+pub fn search(initial_state: State, puzzle: Puzzle) -> Result<(usize, Vec<Move>), CommonError> {
+    let target_state = target_state(&puzzle);
+
+    // Set up priority queue and seed it with source cell
+    let mut q = PriorityQueue::<State, Reverse<usize>>::new();
+    q.push(initial_state, Reverse(heuristic(&initial_state, &puzzle)));
+
+    // Distance of each cell from the source
+    // Gets updated as we find better ways to get to each cell
+    let mut dists = HashMap::from([(initial_state, 0)]);
+
+    // List of visited nodes
+    let mut seen = HashSet::<State>::new();
+
+    // For each state, the state it was reached from and the move that got us there, so the
+    // winning path can be replayed once the target is found
+    let mut predecessors = HashMap::<State, (State, Move)>::new();
+
+    while !q.is_empty() {
+        // Get closest (highest priority) node in queue
+        let (current, _priority) = q.pop().unwrap();
+
+        // This should never happen given the check in the for loop, but I want to know if there is
+        // a regression
+        assert!(!seen.contains(&current));
+
+        // Add the current state to seen
+        seen.insert(current);
+
+        // If the current state is the target state, return the distance to get to this state
+        // along with the moves that got us there
+        if current == target_state {
+            let cost = dists
+                .get(&target_state)
+                .copied()
+                .ok_or(Day23Error::NoSolution)?;
+            return Ok((cost, reconstruct_path(&predecessors, initial_state, target_state)));
+        }
+
+        // Save to current node
+        let current_dist = *dists.get(&current).unwrap_or(&usize::MAX);
+
+        // If there is a possibility for an amphipod to go home, always put that move next
+        let neighbours = if let Some(home) = go_home(current, &puzzle) {
+            vec![home]
+        // Otherwise, consider all possible unblock moves (we will consider the cheapest first to
+        // ensure that we find the cheapest overall solution)
+        } else {
+            get_all_unblock_moves(current, &puzzle)
+        };
+
+        // Add all neighbour moves to queue
+        // Reject already-seen states
+        for neighbour in neighbours.into_iter().filter(|n| !seen.contains(&n.state)) {
+            let neighbour_dist = dists.get(&neighbour.state).unwrap_or(&usize::MAX);
+
+            // If it would be quicker to get to neighbour from current node,
+            // then update the distance
+            let dist_to_neighbour_through_current =
+                current_dist + neighbour.amphipod().unwrap().weight() * neighbour.cost;
+            if dist_to_neighbour_through_current < *neighbour_dist {
+                dists.insert(neighbour.state, dist_to_neighbour_through_current);
+                predecessors.insert(neighbour.state, (current, neighbour));
+            }
+
+            // Enqueue neighbour, ordering by true distance plus the heuristic's estimate of what's
+            // left, not true distance alone
+            let g = *dists.get(&neighbour.state).unwrap();
+            let h = heuristic(&neighbour.state, &puzzle);
+            q.push(neighbour.state, Reverse(g + h));
+        }
+    }
+
+    Err(Day23Error::NoSolution.into())
+}
+
+pub struct Day23;
+
+impl Solution for Day23 {
+    type Parsed = (Vec<Option<Amphipod>>, Vec<Vec<Option<Amphipod>>>);
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, CommonError> {
+        Ok(parse_maze(input)?)
+    }
+
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<String, CommonError> {
+        let (initial_state, puzzle) = build_state(parsed, QuestionPart::One);
+        let (cost, _) = search(initial_state, puzzle)?;
+        Ok(cost.to_string())
+    }
+
+    fn part_two(&self, parsed: &Self::Parsed) -> Result<String, CommonError> {
+        let (initial_state, puzzle) = build_state(parsed, QuestionPart::Two);
+        let (cost, _) = search(initial_state, puzzle)?;
+        Ok(cost.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const INPUT: &str = "#############
+#...........#
+###D#A#A#D###
+  #C#C#B#B#
+  #########";
+
+    #[test]
+    fn test_part_one() -> Result<(), CommonError> {
+        let parsed = parse_maze(INPUT)?;
+        let (input, puzzle) = build_state(&parsed, QuestionPart::One);
+        let (cost, moves) = search(input, puzzle)?;
+        assert_eq!(cost, 14467);
+        assert_eq!(
+            moves.iter().map(|m| m.amphipod().unwrap().weight() * m.cost).sum::<usize>(),
+            cost
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_part_two() -> Result<(), CommonError> {
+        let parsed = parse_maze(INPUT)?;
+        let (input, puzzle) = build_state(&parsed, QuestionPart::Two);
+        let (cost, moves) = search(input, puzzle)?;
+        assert_eq!(cost, 48759);
+        assert_eq!(
+            moves.iter().map(|m| m.amphipod().unwrap().weight() * m.cost).sum::<usize>(),
+            cost
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+//! Day 25: Sea Cucumber.
+
+use crate::grid::{Coord, Grid, Mode};
+use crate::{AdventError, Solution};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Cell {
+    Empty,
+    East,
+    South,
+}
+
+impl Cell {
+    fn parse(c: char) -> Self {
+        match c {
+            '>' => Cell::East,
+            'v' => Cell::South,
+            '.' => Cell::Empty,
+            _ => unreachable!(),
+        }
+    }
+}
+
+type State = Grid<Cell, 2>;
+
+fn step(state: &State, target: Cell, diff: Coord<2>) -> State {
+    state.step(|state, pos| {
+        let ahead = [pos[0] + diff[0], pos[1] + diff[1]];
+        let behind = [pos[0] - diff[0], pos[1] - diff[1]];
+
+        if state[pos] == target && state[ahead] == Cell::Empty {
+            // This cell's cuke moves away.
+            Cell::Empty
+        } else if state[pos] == Cell::Empty && state[behind] == target {
+            // The cuke behind moves in.
+            target
+        } else {
+            state[pos]
+        }
+    })
+}
+
+fn recurse(state: State, depth: usize) -> usize {
+    // Recursively step in each direction until no cukes move
+    let new_state = step(&state, Cell::East, [1, 0]);
+    let new_state = step(&new_state, Cell::South, [0, 1]);
+
+    if state.coords().all(|pos| state[pos] == new_state[pos]) {
+        depth
+    } else {
+        recurse(new_state, depth + 1)
+    }
+}
+
+pub struct Day25;
+
+impl Solution for Day25 {
+    type Parsed = State;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AdventError> {
+        let rows: Vec<Vec<Cell>> = input
+            .lines()
+            .map(|line| line.chars().map(Cell::parse).collect())
+            .collect();
+
+        let height = rows.len();
+        let width = rows[0].len();
+
+        let mut grid = Grid::new([width, height], Cell::Empty, Mode::Toroidal);
+        for (y, row) in rows.into_iter().enumerate() {
+            for (x, cell) in row.into_iter().enumerate() {
+                grid[[x as isize, y as isize]] = cell;
+            }
+        }
+
+        Ok(grid)
+    }
+
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<String, AdventError> {
+        let steps = recurse(parsed.clone(), 1);
+        Ok(steps.to_string())
+    }
+
+    fn part_two(&self, _parsed: &Self::Parsed) -> Result<String, AdventError> {
+        Ok("Merry Christmas!".to_string())
+    }
+}
@@ -0,0 +1,10 @@
+pub mod day03;
+pub mod day04;
+pub mod day07;
+pub mod day08;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day15;
+pub mod day23;
+pub mod day25;
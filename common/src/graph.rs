@@ -0,0 +1,110 @@
+//! A reusable adjacency-list graph for traversal problems.
+//!
+//! `day_12` hand-rolled a `HashMap<Vertex, HashSet<Vertex>>`, inserted both
+//! directions of every edge itself, and recursed by hand to enumerate paths.
+//! `Graph` factors that out: edges go in with `insert_edge` (directed) or
+//! `insert_undirected` (both directions at once), `paths` walks every route
+//! from a source to a node accepted by a caller-supplied predicate (what
+//! `day_12`'s path listing needs), and `dijkstra` finds the cheapest one by
+//! delegating to [`crate::pathfind::dijkstra`] so the search itself isn't
+//! duplicated.
+
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// What to do with `next` while walking a path: keep descending into it,
+/// record the path-so-far (plus `next`) as complete, or prune this branch.
+pub enum Step {
+    Continue,
+    Finish,
+    Prune,
+}
+
+#[derive(Debug, Clone)]
+pub struct Graph<N> {
+    adjacency: HashMap<N, HashSet<N>>,
+}
+
+impl<N: Eq + Hash + Clone> Graph<N> {
+    pub fn new() -> Self {
+        Graph { adjacency: HashMap::new() }
+    }
+
+    /// Add a directed edge `from -> to`.
+    pub fn insert_edge(&mut self, from: N, to: N) {
+        self.adjacency.entry(from).or_insert_with(HashSet::new).insert(to);
+    }
+
+    /// Add edges in both directions between `a` and `b`.
+    pub fn insert_undirected(&mut self, a: N, b: N) {
+        self.insert_edge(a.clone(), b.clone());
+        self.insert_edge(b, a);
+    }
+
+    /// Neighbours of `node`, or an empty iterator if it has none.
+    pub fn neighbours(&self, node: &N) -> impl Iterator<Item = &N> {
+        self.adjacency.get(node).into_iter().flatten()
+    }
+
+    /// Whether `node` has at least one outgoing edge.
+    pub fn contains(&self, node: &N) -> bool {
+        self.adjacency.contains_key(node)
+    }
+
+    /// Every node with at least one outgoing edge.
+    pub fn nodes(&self) -> impl Iterator<Item = &N> {
+        self.adjacency.keys()
+    }
+
+    /// Depth-first enumeration of every path starting at `source`. At each
+    /// node reached, `visit(path_so_far, next)` decides whether to keep
+    /// descending, record `path_so_far + [next]` as a finished path, or
+    /// prune that branch entirely. The traversal itself knows nothing about
+    /// what makes a path valid; rules like "a small cave may only be
+    /// revisited once" live entirely in the closure.
+    pub fn paths<F>(&self, source: N, mut visit: F) -> Vec<Vec<N>>
+    where
+        F: FnMut(&[N], &N) -> Step,
+    {
+        let mut results = Vec::new();
+        let mut path = vec![source];
+        self.walk(&mut path, &mut visit, &mut results);
+        results
+    }
+
+    fn walk<F>(&self, path: &mut Vec<N>, visit: &mut F, results: &mut Vec<Vec<N>>)
+    where
+        F: FnMut(&[N], &N) -> Step,
+    {
+        let current = path.last().expect("path is never empty").clone();
+        for next in self.neighbours(&current).cloned().collect::<Vec<_>>() {
+            match visit(path, &next) {
+                Step::Prune => {}
+                Step::Finish => {
+                    path.push(next);
+                    results.push(path.clone());
+                    path.pop();
+                }
+                Step::Continue => {
+                    path.push(next);
+                    self.walk(path, visit, results);
+                    path.pop();
+                }
+            }
+        }
+    }
+
+    /// Cheapest path from `source` to `target`, weighing each edge by
+    /// `weight(neighbour)`. See [`crate::pathfind::dijkstra`].
+    pub fn dijkstra<FW>(&self, source: N, target: N, weight: FW) -> Option<(u32, Vec<N>)>
+    where
+        FW: Fn(&N) -> u32,
+    {
+        crate::pathfind::dijkstra(
+            source,
+            target,
+            |node| self.neighbours(node).cloned().collect(),
+            weight,
+        )
+    }
+}
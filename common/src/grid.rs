@@ -0,0 +1,197 @@
+//! A reusable N-dimensional grid for cellular simulations.
+//!
+//! `day_25` used to track cuke positions in a `HashMap<(usize, usize), Cuke>`
+//! with hand-rolled `extent_x`/`extent_y` wraparound, and `day_11` hardcoded a
+//! `SIZE = 10` `ndarray` with fragile `into_shape` transposes. `Grid` factors
+//! both of those out: each axis tracks an `offset` (its lowest logical
+//! coordinate) and a `size`, coordinates are signed so the grid can grow in
+//! either direction, and indexing can be bounded (out-of-range is `None`) or
+//! toroidal (indices wrap around).
+
+use std::ops::{Index, IndexMut};
+
+pub type Coord<const N: usize> = [isize; N];
+
+/// Whether out-of-range coordinates are rejected or wrapped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Bounded,
+    Toroidal,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Axis {
+    offset: isize,
+    size: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Grid<T, const N: usize> {
+    axes: [Axis; N],
+    cells: Vec<T>,
+    mode: Mode,
+}
+
+impl<T: Clone, const N: usize> Grid<T, N> {
+    /// Build a grid of the given per-axis sizes (offset 0 on every axis),
+    /// filled with `fill`.
+    pub fn new(sizes: [usize; N], fill: T, mode: Mode) -> Self {
+        let axes = sizes.map(|size| Axis { offset: 0, size });
+        let len = axes.iter().map(|axis| axis.size).product();
+        Grid {
+            axes,
+            cells: vec![fill; len],
+            mode,
+        }
+    }
+
+    pub fn sizes(&self) -> [usize; N] {
+        self.axes.map(|axis| axis.size)
+    }
+
+    /// Every coordinate currently backed by the grid, in row-major order.
+    pub fn coords(&self) -> impl Iterator<Item = Coord<N>> + '_ {
+        (0..self.cells.len()).map(|flat| self.unflatten(flat))
+    }
+
+    fn unflatten(&self, mut flat: usize) -> Coord<N> {
+        let mut coord = [0; N];
+        for i in (0..N).rev() {
+            let size = self.axes[i].size;
+            coord[i] = (flat % size) as isize + self.axes[i].offset;
+            flat /= size;
+        }
+        coord
+    }
+
+    /// Map a (possibly toroidal) logical coordinate to a flat backing index.
+    fn flatten(&self, pos: Coord<N>) -> Option<usize> {
+        let mut flat = 0;
+        for i in 0..N {
+            let axis = self.axes[i];
+            let local = pos[i] - axis.offset;
+            let local = match self.mode {
+                Mode::Bounded => {
+                    if local < 0 || local >= axis.size as isize {
+                        return None;
+                    }
+                    local
+                }
+                Mode::Toroidal => local.rem_euclid(axis.size as isize),
+            };
+            flat = flat * axis.size + local as usize;
+        }
+        Some(flat)
+    }
+
+    pub fn get(&self, pos: Coord<N>) -> Option<&T> {
+        self.flatten(pos).map(|flat| &self.cells[flat])
+    }
+
+    pub fn get_mut(&mut self, pos: Coord<N>) -> Option<&mut T> {
+        self.flatten(pos).map(|flat| &mut self.cells[flat])
+    }
+
+    /// Grow the grid by one cell on every side of every axis, filling the new
+    /// cells with `fill`.
+    pub fn extend(&mut self, fill: T) {
+        let old_axes = self.axes;
+        let new_axes = old_axes.map(|axis| Axis {
+            offset: axis.offset - 1,
+            size: axis.size + 2,
+        });
+        let new_len = new_axes.iter().map(|axis| axis.size).product();
+
+        let mut new_cells = vec![fill; new_len];
+        let old_grid = Grid {
+            axes: old_axes,
+            cells: std::mem::take(&mut self.cells),
+            mode: self.mode,
+        };
+        self.axes = new_axes;
+        for pos in old_grid.coords() {
+            let flat = self.flatten(pos).expect("grew grid must contain old cells");
+            new_cells[flat] = old_grid.get(pos).unwrap().clone();
+        }
+        self.cells = new_cells;
+    }
+
+    /// Widen the grid, if needed, so that `pos` is a valid coordinate.
+    pub fn include(&mut self, pos: Coord<N>, fill: T) {
+        while self.flatten(pos).is_none() {
+            self.extend(fill.clone());
+        }
+    }
+
+    /// Coordinates orthogonally adjacent to `pos` (the 2*N axis-aligned
+    /// neighbours).
+    pub fn orthogonal_neighbours(&self, pos: Coord<N>) -> Vec<Coord<N>> {
+        let mut neighbours = Vec::with_capacity(2 * N);
+        for i in 0..N {
+            for delta in [-1, 1] {
+                let mut neighbour = pos;
+                neighbour[i] += delta;
+                neighbours.push(neighbour);
+            }
+        }
+        neighbours
+    }
+
+    /// Coordinates adjacent to `pos` including diagonals (every point in the
+    /// `3^N - 1` surrounding hypercube).
+    pub fn diagonal_neighbours(&self, pos: Coord<N>) -> Vec<Coord<N>> {
+        let mut deltas = vec![[0isize; N]];
+        for i in 0..N {
+            deltas = deltas
+                .iter()
+                .flat_map(|delta| {
+                    [-1, 0, 1].map(|d| {
+                        let mut delta = *delta;
+                        delta[i] = d;
+                        delta
+                    })
+                })
+                .collect();
+        }
+
+        deltas
+            .into_iter()
+            .filter(|delta| delta.iter().any(|d| *d != 0))
+            .map(|delta| {
+                let mut neighbour = pos;
+                for i in 0..N {
+                    neighbour[i] += delta[i];
+                }
+                neighbour
+            })
+            .collect()
+    }
+
+    /// Apply `next` to every current cell to produce the next generation.
+    /// `next` receives the grid and the coordinate being updated.
+    pub fn step<F: Fn(&Self, Coord<N>) -> T>(&self, next: F) -> Self {
+        let mut cells = Vec::with_capacity(self.cells.len());
+        for pos in self.coords() {
+            cells.push(next(self, pos));
+        }
+        Grid {
+            axes: self.axes,
+            cells,
+            mode: self.mode,
+        }
+    }
+}
+
+impl<T: Clone, const N: usize> Index<Coord<N>> for Grid<T, N> {
+    type Output = T;
+
+    fn index(&self, pos: Coord<N>) -> &T {
+        self.get(pos).expect("position out of bounds")
+    }
+}
+
+impl<T: Clone, const N: usize> IndexMut<Coord<N>> for Grid<T, N> {
+    fn index_mut(&mut self, pos: Coord<N>) -> &mut T {
+        self.get_mut(pos).expect("position out of bounds")
+    }
+}
@@ -0,0 +1,76 @@
+//! Fetching and caching puzzle input from the Advent of Code website.
+//!
+//! Every day used to require piping a manually-downloaded file into stdin.
+//! This module downloads the real input (or, in `--example` mode, the first
+//! example block from the puzzle page) using the session cookie in
+//! `AOC_COOKIE`, and caches whichever one was fetched under `inputs/` so that
+//! subsequent runs work offline.
+
+use crate::AdventError;
+use scraper::{Html, Selector};
+use std::fs;
+use std::path::PathBuf;
+
+const COOKIE_VAR: &str = "AOC_COOKIE";
+
+fn cache_path(day: u8, example: bool) -> PathBuf {
+    let name = if example {
+        format!("{day}.small.txt")
+    } else {
+        format!("{day}.txt")
+    };
+    PathBuf::from("inputs").join(name)
+}
+
+fn session_cookie() -> Result<String, AdventError> {
+    std::env::var(COOKIE_VAR).map_err(|_| AdventError::MissingCookie)
+}
+
+fn get(url: &str, cookie: &str) -> Result<String, AdventError> {
+    ureq::get(url)
+        .set("Cookie", &format!("session={cookie}"))
+        .call()
+        .map_err(|err| AdventError::Fetch(err.to_string()))?
+        .into_string()
+        .map_err(AdventError::Io)
+}
+
+/// Pull the first `<pre><code>` block out of the puzzle page, which is always
+/// the worked example given in the problem statement.
+fn scrape_example(page: &str) -> Result<String, AdventError> {
+    let document = Html::parse_document(page);
+    let selector = Selector::parse("pre code").unwrap();
+    document
+        .select(&selector)
+        .next()
+        .map(|el| el.text().collect::<String>())
+        .ok_or(AdventError::MissingExample)
+}
+
+/// Get the puzzle input for `(year, day)`, downloading and caching it under
+/// `inputs/` on first use. When `example` is set, fetch the worked example
+/// from the puzzle page instead of the real input.
+pub fn fetch(year: u16, day: u8, example: bool) -> Result<String, AdventError> {
+    let path = cache_path(day, example);
+    if let Ok(cached) = fs::read_to_string(&path) {
+        return Ok(cached);
+    }
+
+    let cookie = session_cookie()?;
+    let body = if example {
+        let page = get(&format!("https://adventofcode.com/{year}/day/{day}"), &cookie)?;
+        scrape_example(&page)?
+    } else {
+        get(
+            &format!("https://adventofcode.com/{year}/day/{day}/input"),
+            &cookie,
+        )?
+    };
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, &body)?;
+
+    Ok(body)
+}
@@ -0,0 +1,101 @@
+//! Small `nom`-based building blocks for puzzle input parsing: primitive
+//! field parsers (`signed`, `unsigned`, `rest_of_line`) meant to be combined
+//! with [`crate::parser!`], plus the scanner/coordinate-block parsers first
+//! pulled out of Day 19's hand-rolled parsing but generic enough for other
+//! days with a similar shape to share.
+
+use nom::bytes::complete::tag;
+use nom::character::complete::{char, digit1, line_ending, not_line_ending};
+use nom::combinator::{map, map_res, opt, recognize};
+use nom::multi::separated_list1;
+use nom::sequence::{delimited, pair, preceded, separated_pair};
+use nom::IResult;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("Failed to parse input at byte offset {offset}, near `{remaining}'.")]
+pub struct ParseError {
+    pub offset: usize,
+    pub remaining: String,
+}
+
+impl ParseError {
+    fn from_nom(original: &str, err: nom::Err<nom::error::Error<&str>>) -> Self {
+        let remaining = match &err {
+            nom::Err::Error(e) | nom::Err::Failure(e) => e.input,
+            nom::Err::Incomplete(_) => "",
+        };
+        ParseError {
+            offset: original.len() - remaining.len(),
+            remaining: remaining.chars().take(40).collect(),
+        }
+    }
+}
+
+/// Run a parser (typically one built with [`crate::parser!`]) over a whole
+/// input, turning any failure into a [`ParseError`] with a byte offset
+/// instead of the bare `nom` error.
+pub fn run<'a, T>(
+    mut parser: impl FnMut(&'a str) -> IResult<&'a str, T>,
+    input: &'a str,
+) -> Result<T, ParseError> {
+    parser(input)
+        .map(|(_, value)| value)
+        .map_err(|err| ParseError::from_nom(input, err))
+}
+
+/// A signed base-10 integer, e.g. for day 19's coordinates.
+pub fn signed(input: &str) -> IResult<&str, i32> {
+    map_res(recognize(pair(opt(char('-')), digit1)), str::parse)(input)
+}
+
+/// An unsigned base-10 integer.
+pub fn unsigned(input: &str) -> IResult<&str, u64> {
+    map_res(digit1, str::parse)(input)
+}
+
+/// A signed base-10 integer with a wider range than [`signed`], for
+/// coordinates that aren't known to stay within `i32`.
+pub fn signed64(input: &str) -> IResult<&str, i64> {
+    map_res(recognize(pair(opt(char('-')), digit1)), str::parse)(input)
+}
+
+/// Everything up to (not including) the next line ending.
+pub fn rest_of_line(input: &str) -> IResult<&str, &str> {
+    not_line_ending(input)
+}
+
+/// One `x,y,z` coordinate line.
+pub fn point(input: &str) -> IResult<&str, (i32, i32, i32)> {
+    map(
+        pair(
+            signed,
+            pair(preceded(char(','), signed), preceded(char(','), signed)),
+        ),
+        |(x, (y, z))| (x, y, z),
+    )(input)
+}
+
+/// One `--- scanner N ---` header plus the list of points it scanned.
+pub struct ScannerBlock {
+    pub index: usize,
+    pub points: Vec<(i32, i32, i32)>,
+}
+
+fn header(input: &str) -> IResult<&str, usize> {
+    map_res(delimited(tag("--- scanner "), digit1, tag(" ---")), str::parse)(input)
+}
+
+fn scanner(input: &str) -> IResult<&str, ScannerBlock> {
+    map(
+        separated_pair(header, line_ending, separated_list1(line_ending, point)),
+        |(index, points)| ScannerBlock { index, points },
+    )(input)
+}
+
+/// Parse a whole puzzle input of scanner blocks separated by a blank line.
+pub fn scanners(input: &str) -> Result<Vec<ScannerBlock>, ParseError> {
+    separated_list1(tag("\n\n"), scanner)(input)
+        .map(|(_, blocks)| blocks)
+        .map_err(|err| ParseError::from_nom(input, err))
+}
@@ -0,0 +1,138 @@
+//! Shortest-path search shared between days that need it.
+//!
+//! `day_15`'s `dijkstra` was hand-rolled against `priority_queue` and only
+//! did uniform-cost search. This module keeps that algorithm but also offers
+//! `a_star`, which prunes the search with an admissible heuristic. Both take
+//! closures for neighbours and edge weights so callers aren't tied to any
+//! particular graph representation.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+/// A node paired with its priority (`f = g + h`, or just `g` for Dijkstra),
+/// ordered so that `BinaryHeap` (a max-heap) pops the lowest priority first.
+struct Entry<N> {
+    priority: u32,
+    node: N,
+}
+
+impl<N> PartialEq for Entry<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<N> Eq for Entry<N> {}
+
+impl<N> PartialOrd for Entry<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N> Ord for Entry<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+/// Reconstruct the path from `source` to `target` out of a `came_from` map.
+fn unwind<N: Eq + Hash + Clone>(came_from: &HashMap<N, N>, target: N) -> Vec<N> {
+    let mut path = vec![target.clone()];
+    let mut current = target;
+    while let Some(prev) = came_from.get(&current) {
+        path.push(prev.clone());
+        current = prev.clone();
+    }
+    path.reverse();
+    path
+}
+
+/// Search from `source` to `target`, relaxing neighbours by `weight(neighbour)`
+/// and guiding the search with `heuristic(node)` (an admissible lower bound on
+/// the remaining distance to `target`, or `|_| 0` for plain Dijkstra).
+/// Returns the total cost and the path, if `target` is reachable.
+fn search<N, FN, FW, FH>(
+    source: N,
+    target: N,
+    neighbours: FN,
+    weight: FW,
+    heuristic: FH,
+) -> Option<(u32, Vec<N>)>
+where
+    N: Eq + Hash + Clone,
+    FN: Fn(&N) -> Vec<N>,
+    FW: Fn(&N) -> u32,
+    FH: Fn(&N) -> u32,
+{
+    let mut open = BinaryHeap::new();
+    open.push(Entry {
+        priority: heuristic(&source),
+        node: source.clone(),
+    });
+
+    let mut g_score = HashMap::from([(source.clone(), 0)]);
+    let mut came_from = HashMap::<N, N>::new();
+
+    while let Some(Entry { priority, node: current }) = open.pop() {
+        // Stale entry: we've since found a cheaper way to `current`.
+        let current_g = *g_score.get(&current).unwrap_or(&u32::MAX);
+        if priority > current_g + heuristic(&current) {
+            continue;
+        }
+
+        if current == target {
+            return Some((current_g, unwind(&came_from, current)));
+        }
+
+        for neighbour in neighbours(&current) {
+            let tentative_g = current_g + weight(&neighbour);
+            if tentative_g < *g_score.get(&neighbour).unwrap_or(&u32::MAX) {
+                g_score.insert(neighbour.clone(), tentative_g);
+                came_from.insert(neighbour.clone(), current.clone());
+                open.push(Entry {
+                    priority: tentative_g + heuristic(&neighbour),
+                    node: neighbour,
+                });
+            }
+        }
+    }
+
+    None
+}
+
+/// Uniform-cost search: visits nodes strictly in order of distance from
+/// `source`.
+pub fn dijkstra<N, FN, FW>(source: N, target: N, neighbours: FN, weight: FW) -> Option<(u32, Vec<N>)>
+where
+    N: Eq + Hash + Clone,
+    FN: Fn(&N) -> Vec<N>,
+    FW: Fn(&N) -> u32,
+{
+    search(source, target, neighbours, weight, |_| 0)
+}
+
+/// Search guided by `heuristic`, an admissible lower bound on the remaining
+/// cost to `target` (one that never overestimates it).
+pub fn a_star<N, FN, FW, FH>(
+    source: N,
+    target: N,
+    neighbours: FN,
+    weight: FW,
+    heuristic: FH,
+) -> Option<(u32, Vec<N>)>
+where
+    N: Eq + Hash + Clone,
+    FN: Fn(&N) -> Vec<N>,
+    FW: Fn(&N) -> u32,
+    FH: Fn(&N) -> u32,
+{
+    search(source, target, neighbours, weight, heuristic)
+}
+
+/// Manhattan distance, an admissible heuristic for grids where every move
+/// costs at least 1.
+pub fn manhattan((x1, y1): (usize, usize), (x2, y2): (usize, usize)) -> u32 {
+    (x1 as i64 - x2 as i64).unsigned_abs() as u32 + (y1 as i64 - y2 as i64).unsigned_abs() as u32
+}
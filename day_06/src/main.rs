@@ -1,106 +1,134 @@
-use memoize::memoize;
-use std::env;
-use std::io::{stdin, BufRead};
-use std::process::exit;
+use common::cli::{self, AdventArgs, Answer, CliError};
+use common::QuestionPart;
 use thiserror::Error;
 
-enum QuestionPart {
-    One,
-    Two,
-}
-
 #[derive(Error, Debug)]
 pub enum AdventError {
+    #[error(transparent)]
+    Cli(#[from] CliError),
+
     #[error("Invalid input")]
     InvalidInput,
 
-    #[error("Invalid command `{command:?}'. Expected `part-one' or `part-two'.")]
-    InvalidCommand { command: String },
+    #[error("Invalid `--days' value `{value}'. Expected a non-negative number.")]
+    InvalidDays { value: String },
+}
 
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
+// Above this many days, simulating one bucket-rotation per day is more work than raising the
+// 9x9 transition matrix to the `days`th power and applying it once.
+const MATRIX_THRESHOLD: u64 = 1_000;
+
+// Fish counts are stored as `u128`, not `u64`: real inputs grow the total population by roughly
+// 1.1x per day, so a large `--days` (the matrix path below exists precisely to make such values
+// feasible) would overflow `u64` well before `u64::MAX`. `u128` pushes that ceiling far out of
+// reach of any `--days` value this binary could finish computing anyway.
+type Counts = [u128; 9];
+type Matrix = [[u128; 9]; 9];
+
+fn parse(input: &str) -> Result<Counts, AdventError> {
+    let mut counts = [0u128; 9];
+    for x in input.trim().split(',') {
+        let timer: usize = x.parse().map_err(|_| AdventError::InvalidInput)?;
+        *counts.get_mut(timer).ok_or(AdventError::InvalidInput)? += 1;
+    }
+    Ok(counts)
+}
 
-    #[error("Please specify `part-one' or `part-two' as the first argument.")]
-    NoPartArgument,
+// One day: every fish's timer ticks down by one, a timer of 0 spawns a new fish at timer 8 and
+// resets itself to 6. Modeled as a left-rotation of the bucket counts (so `counts[t] =
+// counts[t + 1]`), with the spawned fish folded in afterwards instead of being tracked per-fish.
+fn step(counts: Counts) -> Counts {
+    let mut next = [0u128; 9];
+    next[..8].copy_from_slice(&counts[1..9]);
+    next[6] += counts[0];
+    next[8] = counts[0];
+    next
 }
 
-fn part_one(days_remaining: i32, state: &[i8]) -> Result<usize, AdventError> {
-    // For part one, I modeled it exactly as described
-    // I keep the number of each fish in a big vec and updated it each day,
-    // then returned the length
+fn simulate_linear(mut counts: Counts, days: u64) -> Counts {
+    for _ in 0..days {
+        counts = step(counts);
+    }
+    counts
+}
 
-    if days_remaining == 0 {
-        return Ok(state.len());
+// The transition matrix `M` for one day, derived by running `step` on each basis vector rather
+// than transcribing the rotation by hand: column `j` of `M` is `step(e_j)`.
+fn transition_matrix() -> Matrix {
+    let mut matrix = [[0u128; 9]; 9];
+    for j in 0..9 {
+        let mut basis = [0u128; 9];
+        basis[j] = 1;
+        let column = step(basis);
+        for i in 0..9 {
+            matrix[i][j] = column[i];
+        }
     }
-    let state = state
-        .iter()
-        .map(|x| match x {
-            0 => vec![6, 8],
-            _ => vec![x - 1],
-        })
-        .flatten()
-        .collect::<Vec<_>>();
-    // println!("Days remaining {} days: {:?}", days_remaining, state);
-
-    part_one(days_remaining - 1, &state[..])
+    matrix
 }
 
-#[memoize]
-fn part_two(x: i8, i: i32) -> usize {
-    // For the second part, the previous method did not work
-    // The size of the state vector grows exponentially with days,
-    // as it doubles in size on average every 7 days
+fn matrix_mul(a: &Matrix, b: &Matrix) -> Matrix {
+    let mut result = [[0u128; 9]; 9];
+    for (i, row) in result.iter_mut().enumerate() {
+        for (j, cell) in row.iter_mut().enumerate() {
+            *cell = (0..9).map(|k| a[i][k] * b[k][j]).sum();
+        }
+    }
+    result
+}
 
-    // I tried to do something clever similar to 2^(days/7),
-    // but the pesky "new fish take slightly longer" complicates this
+fn matrix_vec_mul(m: &Matrix, v: Counts) -> Counts {
+    std::array::from_fn(|i| (0..9).map(|j| m[i][j] * v[j]).sum())
+}
 
-    // I worked out this recursion on some paper, and after memoizing,
-    // it can be run very quickly
+fn identity_matrix() -> Matrix {
+    std::array::from_fn(|i| std::array::from_fn(|j| if i == j { 1 } else { 0 }))
+}
 
-    if i == 0 {
-        return 1;
+fn matrix_pow(mut base: Matrix, mut exponent: u64) -> Matrix {
+    let mut result = identity_matrix();
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = matrix_mul(&result, &base);
+        }
+        base = matrix_mul(&base, &base);
+        exponent >>= 1;
     }
-    match x {
-        0 => part_two(6, i - 1) + part_two(8, i - 1),
-        _ => part_two(x - 1, i - 1),
+    result
+}
+
+fn simulate_matrix(counts: Counts, days: u64) -> Counts {
+    matrix_vec_mul(&matrix_pow(transition_matrix(), days), counts)
+}
+
+fn simulate(counts: Counts, days: u64) -> Counts {
+    if days <= MATRIX_THRESHOLD {
+        simulate_linear(counts, days)
+    } else {
+        simulate_matrix(counts, days)
     }
 }
 
-fn day_6() -> Result<(), AdventError> {
-    let args: Vec<String> = env::args().collect();
-    let command = args.get(1).ok_or(AdventError::NoPartArgument)?;
-    let question_part = match &command[..] {
-        "part-one" => Ok(QuestionPart::One),
-        "part-two" => Ok(QuestionPart::Two),
-        _ => Err(AdventError::InvalidCommand {
-            command: args[1].to_string(),
-        }),
-    }?;
-
-    let mut input = String::new();
-    stdin().lock().read_line(&mut input)?;
-
-    let initial_state = input
-        .trim()
-        .split(',')
-        .map(|x| x.parse().map_err(|_| AdventError::InvalidInput))
-        .collect::<Result<Vec<i8>, AdventError>>()?;
-
-    let days = match question_part {
+fn solve(args: &AdventArgs, input: String) -> Result<Answer, AdventError> {
+    let counts = parse(&input)?;
+    let default_days = match args.question_part {
         QuestionPart::One => 80,
         QuestionPart::Two => 256,
     };
-    let result = match question_part {
-        QuestionPart::One => part_one(days, &initial_state[..])?,
-        QuestionPart::Two => initial_state.iter().map(|x| part_two(*x, days)).sum(),
+    let days = match args.flag_value("--days") {
+        Some(value) => value.parse().map_err(|_| AdventError::InvalidDays {
+            value: value.to_string(),
+        })?,
+        None => default_days,
     };
-    println!("{}", result);
-    Ok(())
+
+    let total: u128 = simulate(counts, days).iter().sum();
+    Ok(match args.question_part {
+        QuestionPart::One => Answer::PartOne(total.to_string()),
+        QuestionPart::Two => Answer::PartTwo(total.to_string()),
+    })
 }
 
 fn main() {
-    day_6().unwrap_or_else(|err| {
-        eprintln!("{}", err);
-        exit(1);
-    });
+    cli::run(solve)
 }
@@ -1,33 +1,13 @@
+use common::cli::{self, AdventArgs, Answer, CliError};
+use common::QuestionPart;
 use itertools::Itertools;
-use ndarray::prelude::*;
-use ndarray::{Array, ShapeError, Slice};
+use ndarray::{Array, Ix2};
 use regex::Regex;
-use std::env;
-use std::fmt;
-use std::io::{stdin, BufRead};
-use std::process::exit;
+use std::collections::HashSet;
 use thiserror::Error;
 
-#[derive(Debug)]
-enum QuestionPart {
-    One,
-    Two,
-}
-
-#[derive(Debug)]
-enum Answer {
-    PartOne(usize),
-    PartTwo(String),
-}
-
-impl fmt::Display for Answer {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            Answer::PartOne(v) => write!(f, "{}", v),
-            Answer::PartTwo(s) => write!(f, "{}", s),
-        }
-    }
-}
+mod ocr;
+mod raster;
 
 #[derive(Debug)]
 enum Fold {
@@ -37,8 +17,8 @@ enum Fold {
 
 #[derive(Error, Debug)]
 pub enum AdventError {
-    #[error("Invalid command `{command:?}'. Expected `part-one' or `part-two'.")]
-    InvalidCommand { command: String },
+    #[error(transparent)]
+    Cli(#[from] CliError),
 
     #[error("Invalid input detected.")]
     InvalidInput,
@@ -46,18 +26,9 @@ pub enum AdventError {
     #[error("Your transparent paper has no dots!")]
     NoDots,
 
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
-
     #[error(transparent)]
     Regex(#[from] regex::Error),
 
-    #[error(transparent)]
-    Shape(#[from] ShapeError),
-
-    #[error("Please specify `part-one' or `part-two' as the first argument.")]
-    NoPartArgument,
-
     #[error(transparent)]
     Parse(#[from] std::num::ParseIntError),
 
@@ -66,43 +37,40 @@ pub enum AdventError {
 
     #[error("Invalid fold `{line}'. Expected `fold along <x|y>=<num>`.")]
     FoldFormat { line: String },
-}
-
-fn print(a: &Array<bool, Ix2>) {
-    // Pretty print the array like in the website
 
-    for row in a.rows() {
-        println!(
-            "{}",
-            row.iter().map(|x| if *x { '#' } else { '.' }).join("")
-        );
-    }
+    #[error("Failed to write image to `{path}': {source}")]
+    Image {
+        path: String,
+        #[source]
+        source: image::ImageError,
+    },
+
+    #[error("Failed to write animation to `{path}': {source}")]
+    Animate {
+        path: String,
+        #[source]
+        source: image::ImageError,
+    },
 }
 
-fn abs_diff(a: usize, b: usize) -> usize {
-    if a > b {
-        a - b
-    } else {
-        b - a
+// Reflect a single dot across a fold, or `None` if it sits exactly on the fold line (which is
+// never kept). Works regardless of whether the fold line is the exact midpoint of the paper: dots
+// past it are mirrored with `2 * at - coord`, which can land past where the near half used to end.
+fn fold_point(fold: &Fold, (x, y): (usize, usize)) -> Option<(usize, usize)> {
+    match *fold {
+        Fold::X(at) if x == at => None,
+        Fold::X(at) if x > at => Some((2 * at - x, y)),
+        Fold::X(_) => Some((x, y)),
+        Fold::Y(at) if y == at => None,
+        Fold::Y(at) if y > at => Some((x, 2 * at - y)),
+        Fold::Y(_) => Some((x, y)),
     }
 }
 
-fn day_13() -> Result<Answer, AdventError> {
-    let args: Vec<String> = env::args().collect();
-    let command = args.get(1).ok_or(AdventError::NoPartArgument)?;
-    let question_part = match &command[..] {
-        "part-one" => Ok(QuestionPart::One),
-        "part-two" => Ok(QuestionPart::Two),
-        _ => Err(AdventError::InvalidCommand {
-            command: args[1].to_string(),
-        }),
-    }?;
-
-    let input = stdin()
-        .lock()
-        .lines()
-        .map(|l| l.map_err(AdventError::Io))
-        .collect::<Result<Vec<_>, AdventError>>()?;
+fn solve(args: &AdventArgs, input: String) -> Result<Answer, AdventError> {
+    let question_part = args.question_part;
+
+    let input = input.lines().map(str::to_string).collect::<Vec<_>>();
     let (dots, folds) = input
         .split(|l| l.is_empty())
         .collect_tuple()
@@ -139,69 +107,37 @@ fn day_13() -> Result<Answer, AdventError> {
         })
         .collect::<Result<Vec<Fold>, AdventError>>()?;
 
-    let width = *dots
+    let mut width = *dots
         .iter()
         .map(|(x, _y)| x)
         .max()
         .ok_or(AdventError::NoDots)?
         + 1;
-    let height = *dots
+    let mut height = *dots
         .iter()
         .map(|(_x, y)| y)
         .max()
         .ok_or(AdventError::NoDots)?
         + 1;
-    let mut a = Array::from_elem((width, height), false);
+    let mut dots: HashSet<(usize, usize)> = dots.into_iter().collect();
 
-    for (x, y) in dots {
-        if let Some(v) = a.get_mut((x, y)) {
-            *v = true;
-        }
-    }
+    // One snapshot per fold, for `--animate`. Every later frame is the same size or smaller than
+    // the first (folds only ever shrink the paper), so the first frame's dimensions make a stable
+    // canvas to pad the rest against.
+    let mut frames: Vec<Array<bool, Ix2>> = Vec::new();
 
     for fold in folds {
-        // Get stuff needed to make the slice
-        let (axis, position) = match fold {
-            Fold::X(v) => (Axis(0), v),
-            Fold::Y(v) => (Axis(1), v),
-        };
-        let (v1, v2) = a.view().split_at(axis, position);
-        // The line of the fold is not kept
-        let mut v2 = v2.slice_axis(axis, Slice::from(1..));
-
-        // Flip the other part
-        // We always fold up or left
-        // The part that gets folded gets mirrored
-        v2.invert_axis(axis);
-
-        // Get the missing width / height of the smaller part
-        // We must make them the same shape before we broadcast
-        let (missing_width, missing_height) = match fold {
-            Fold::Y(_) => (v2.nrows(), abs_diff(v2.ncols(), v1.ncols())),
-            Fold::X(_) => (abs_diff(v2.nrows(), v1.nrows()), v2.ncols()),
-        };
-
-        // Make an empty array of the missing width and height
-        // This is like the virtual paper over the end of the real paper
-        let mut zeros = Array::from_elem((missing_width, missing_height), false);
-
-        // Grow the smaller part so that both parts are the same shape
-        let (v1, v2) = if v1.shape() < v2.shape() {
-            // Append the top/left view to the end of the zeros
-            // If we're folding up or left, the blank space needs to go at the start
-            zeros.append(axis, v1)?;
-            (zeros, v2.to_owned())
-        } else {
-            // Append the bottom/right view to the end of the zeros
-            // If this part is smaller, then the blank space should go at the END before folding
-            // We already did `invert_axis` (folded), so it should go at the START just like the
-            // other case
-            zeros.append(axis, v2)?;
-            (v1.to_owned(), zeros)
-        };
-
-        // Laminate transparent paper into single sheet
-        a = &v1 | &v2;
+        dots = dots.into_iter().filter_map(|dot| fold_point(&fold, dot)).collect();
+        match fold {
+            Fold::X(at) => width = at,
+            Fold::Y(at) => height = at,
+        }
+
+        let mut frame = Array::from_elem((height, width), false);
+        for &(x, y) in &dots {
+            frame[[y, x]] = true;
+        }
+        frames.push(frame);
 
         // In part one, we only do the first fold
         if matches!(question_part, QuestionPart::One) {
@@ -209,27 +145,50 @@ fn day_13() -> Result<Answer, AdventError> {
         }
     }
 
+    let raw = args.has_flag("--raw");
+    let image_path = args.flag_value("--image");
+    let animate_path = args.flag_value("--animate");
+    let scale = args
+        .flag_value("--scale")
+        .map(str::parse::<u32>)
+        .transpose()
+        .map_err(AdventError::Parse)?
+        .unwrap_or(1);
+
     Ok(match question_part {
-        QuestionPart::One => Answer::PartOne(a.iter().filter(|x| **x).count()),
+        QuestionPart::One => Answer::PartOne(dots.len().to_string()),
         QuestionPart::Two => {
-            // Print result
-            Answer::PartTwo(
-                a.reversed_axes()
-                    .rows()
+            // Row-major (y, x), matching both the raw dump and the OCR lookup table
+            let mut grid = Array::from_elem((height, width), false);
+            for (x, y) in dots {
+                grid[[y, x]] = true;
+            }
+            Answer::PartTwo(if let Some(path) = animate_path {
+                raster::animate(&frames, scale, path).map_err(|source| AdventError::Animate {
+                    path: path.to_string(),
+                    source,
+                })?;
+                format!("Wrote a {}-frame animation to `{path}'", frames.len())
+            } else if let Some(path) = image_path {
+                raster::rasterize(&grid, scale)
+                    .save(path)
+                    .map_err(|source| AdventError::Image {
+                        path: path.to_string(),
+                        source,
+                    })?;
+                format!("Wrote {width}x{height} image to `{path}'")
+            } else if raw {
+                grid.rows()
                     .into_iter()
                     .map(|row| row.iter().map(|x| if *x { '#' } else { '.' }).join(""))
-                    .join("\n"),
-            )
+                    .join("\n")
+            } else {
+                ocr::decode(&grid)
+            })
         }
     })
 }
 
 fn main() {
-    match day_13() {
-        Ok(answer) => println!("{}", answer),
-        Err(err) => {
-            eprintln!("{}", err);
-            exit(1);
-        }
-    }
+    cli::run(solve)
 }
@@ -0,0 +1,76 @@
+//! Decode the capital letters Advent of Code renders into the folded paper in part two. Each
+//! letter is drawn on a 6-row-tall, 4-column-wide block, with a single blank column of padding
+//! between letters (stride 5), so the grid can be read by slicing it into successive 4x6
+//! sub-blocks and matching each against a lookup table of known glyphs.
+
+use ndarray::{s, Array, ArrayView2, Ix2};
+
+const GLYPH_HEIGHT: usize = 6;
+const GLYPH_WIDTH: usize = 4;
+const STRIDE: usize = GLYPH_WIDTH + 1;
+
+// Canonical 4x6 pixel patterns for the capital letters Advent of Code is known to render, one
+// string per row, read top to bottom.
+const GLYPHS: &[(char, [&str; GLYPH_HEIGHT])] = &[
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#..#", "#..#", ".##.", "..#.", "..#.", "..#."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+fn render(grid: &ArrayView2<bool>) -> String {
+    grid.rows()
+        .into_iter()
+        .map(|row| row.iter().map(|&x| if x { '#' } else { '.' }).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+fn match_glyph(block: &ArrayView2<bool>) -> Option<char> {
+    GLYPHS
+        .iter()
+        .find(|(_, pattern)| {
+            block
+                .rows()
+                .into_iter()
+                .zip(pattern.iter())
+                .all(|(row, expected)| row.iter().zip(expected.chars()).all(|(&cell, c)| cell == (c == '#')))
+        })
+        .map(|(letter, _)| *letter)
+}
+
+/// Decode the letters drawn into a grid that's `row`-major (i.e. `grid[[row, col]]`, with `row`
+/// running down the page). Any 4x6 block that doesn't match a known glyph is rendered back as its
+/// raw `.`/`#` rows (joined by `|`) instead of a letter, so unusual inputs still produce output.
+pub fn decode(grid: &Array<bool, Ix2>) -> String {
+    let (height, width) = grid.dim();
+    if height != GLYPH_HEIGHT {
+        return render(&grid.view());
+    }
+
+    (0..width)
+        .step_by(STRIDE)
+        .map(|start| {
+            let end = (start + GLYPH_WIDTH).min(width);
+            let block = grid.slice(s![.., start..end]);
+            match match_glyph(&block) {
+                Some(letter) => letter.to_string(),
+                None => render(&block),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,105 @@
+//! Rasterize the folded paper into a one-pixel(-or-more)-per-dot image, for visually inspecting
+//! the fold output instead of (or alongside) the OCR decoding in [`crate::ocr`].
+
+use image::codecs::gif::GifEncoder;
+use image::{DynamicImage, Frame, ImageBuffer, ImageResult, Rgb, RgbImage};
+use ndarray::{s, Array, Ix2};
+use std::fs::File;
+
+const FOREGROUND: Rgb<u8> = Rgb([255, 255, 255]);
+const BACKGROUND: Rgb<u8> = Rgb([0, 0, 0]);
+
+/// Render `grid` (row-major, `grid[[row, col]]`) to an `RgbImage`, scaling each cell up to a
+/// `scale`x`scale` block of pixels so a one-dot-per-pixel image is actually visible when viewed.
+pub fn rasterize(grid: &Array<bool, Ix2>, scale: u32) -> RgbImage {
+    let (height, width) = grid.dim();
+    let scale = scale.max(1);
+
+    ImageBuffer::from_fn(width as u32 * scale, height as u32 * scale, |x, y| {
+        let (col, row) = ((x / scale) as usize, (y / scale) as usize);
+        if grid[[row, col]] {
+            FOREGROUND
+        } else {
+            BACKGROUND
+        }
+    })
+}
+
+/// Grow `frame` up to `(height, width)`, keeping its existing dots anchored at the top-left
+/// corner (where they already sit, since folding never moves the near half of the paper).
+fn pad(frame: &Array<bool, Ix2>, height: usize, width: usize) -> Array<bool, Ix2> {
+    let (frame_height, frame_width) = frame.dim();
+    let mut padded = Array::from_elem((height, width), false);
+    padded
+        .slice_mut(s![..frame_height, ..frame_width])
+        .assign(frame);
+    padded
+}
+
+/// Write `frames` out as a single animated GIF at `path`, one frame per fold, each padded to the
+/// first frame's dimensions so the canvas doesn't jump around as the paper shrinks.
+pub fn animate(frames: &[Array<bool, Ix2>], scale: u32, path: &str) -> ImageResult<()> {
+    let Some((first, rest)) = frames.split_first() else {
+        return Ok(());
+    };
+    let (height, width) = first.dim();
+
+    let file = File::create(path).map_err(image::ImageError::IoError)?;
+    let mut encoder = GifEncoder::new(file);
+    encoder.encode_frame(Frame::new(to_rgba(rasterize(first, scale))))?;
+    for frame in rest {
+        encoder.encode_frame(Frame::new(to_rgba(rasterize(&pad(frame, height, width), scale))))?;
+    }
+    Ok(())
+}
+
+// `Frame::new` wants an `RgbaImage`; `rasterize` deals in `RgbImage` since dots are never
+// translucent, so the alpha channel only needs to be added here, right before encoding.
+fn to_rgba(image: RgbImage) -> image::RgbaImage {
+    DynamicImage::ImageRgb8(image).to_rgba8()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    #[test]
+    fn rasterize_scales_each_cell_up_by_scale() {
+        let grid = array![[true, false], [false, true]];
+        let image = rasterize(&grid, 2);
+
+        assert_eq!(image.dimensions(), (4, 4));
+        assert_eq!(*image.get_pixel(0, 0), FOREGROUND);
+        assert_eq!(*image.get_pixel(1, 1), FOREGROUND);
+        assert_eq!(*image.get_pixel(2, 0), BACKGROUND);
+        assert_eq!(*image.get_pixel(3, 2), FOREGROUND);
+        assert_eq!(*image.get_pixel(0, 2), BACKGROUND);
+    }
+
+    #[test]
+    fn pad_anchors_existing_dots_at_top_left() {
+        let grid = array![[true, false]];
+        let padded = pad(&grid, 3, 3);
+
+        assert_eq!(padded.dim(), (3, 3));
+        assert!(padded[[0, 0]]);
+        assert!(!padded[[0, 1]]);
+        assert!(!padded[[1, 0]]);
+        assert!(!padded[[2, 2]]);
+    }
+
+    #[test]
+    fn animate_writes_a_frame_per_input() {
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("day13-raster-test-{}.gif", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        let frames = vec![array![[true, false]], array![[true]]];
+        animate(&frames, 1, path).unwrap();
+
+        let metadata = std::fs::metadata(path).unwrap();
+        assert!(metadata.len() > 0);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -11,11 +11,12 @@ use thiserror::Error;
 enum QuestionPart {
     One,
     Two,
+    Steps(usize),
 }
 
 #[derive(Error, Debug)]
 pub enum AdventError {
-    #[error("Invalid command `{command:?}'. Expected `part-one' or `part-two'.")]
+    #[error("Invalid command `{command:?}'. Expected `part-one', `part-two', or `steps'.")]
     InvalidCommand { command: String },
 
     #[error("Invalid input detected")]
@@ -33,8 +34,14 @@ pub enum AdventError {
     #[error(transparent)]
     Io(#[from] std::io::Error),
 
-    #[error("Please specify `part-one' or `part-two' as the first argument.")]
+    #[error("Please specify `part-one', `part-two', or `steps' as the first argument.")]
     NoPartArgument,
+
+    #[error("Please specify the number of steps as the second argument to `steps'.")]
+    NoStepsArgument,
+
+    #[error("Could not parse `{arg}' as a number of steps.")]
+    InvalidStepsArgument { arg: String },
 }
 
 // Small wrapper around a hashmap to add some function programming niceties
@@ -143,12 +150,89 @@ fn recurse(
     )
 }
 
+// Above this many steps, `recurse`'s O(pairs * steps) walk is infeasible
+// (and at 10^9 steps, outright impossible), so `day_14` switches to matrix
+// exponentiation instead.
+const MAX_LINEAR_STEPS: usize = 1_000;
+
+type Matrix = Vec<Vec<u128>>;
+
+fn matrix_mul(a: &Matrix, b: &Matrix) -> Matrix {
+    let n = a.len();
+    (0..n)
+        .map(|i| (0..n).map(|j| (0..n).map(|k| a[i][k] * b[k][j]).sum()).collect())
+        .collect()
+}
+
+// Binary exponentiation: O(n^3 log(exponent)) instead of O(n^3 * exponent)
+// for repeated multiplication.
+fn matrix_pow(mut base: Matrix, mut exponent: usize) -> Matrix {
+    let n = base.len();
+    let mut result: Matrix = (0..n)
+        .map(|i| (0..n).map(|j| u128::from(i == j)).collect())
+        .collect();
+    while exponent > 0 {
+        if exponent % 2 == 1 {
+            result = matrix_mul(&result, &base);
+        }
+        base = matrix_mul(&base, &base);
+        exponent /= 2;
+    }
+    result
+}
+
+// Same result as `recurse`, but pair insertion is a fixed linear map on the
+// vector of pair counts, so applying it `steps` times is multiplication by
+// `M^steps`, built once and computed by repeated squaring. This makes
+// `steps` far beyond what `recurse` could ever walk (even 10^9) tractable.
+fn recurse_matrix(
+    pair_insertions: &HashMap<Key, char>,
+    steps: usize,
+    counter: Counter<Key>,
+) -> RecurseResult {
+    let mut pairs: Vec<Key> = pair_insertions.keys().copied().collect();
+    pairs.sort_unstable();
+    let index: HashMap<Key, usize> = pairs.iter().copied().zip(0..).collect();
+    let n = pairs.len();
+
+    let mut transition = vec![vec![0u128; n]; n];
+    for (&(a, b), &inserted) in pair_insertions {
+        let from = index[&(a, b)];
+        transition[index[&(a, inserted)]][from] += 1;
+        transition[index[&(inserted, b)]][from] += 1;
+    }
+    let transition = matrix_pow(transition, steps);
+
+    let initial: Vec<u128> = pairs
+        .iter()
+        .map(|pair| *counter.counts.get(pair).unwrap_or(&0) as u128)
+        .collect();
+
+    Ok(Counter {
+        counts: pairs
+            .into_iter()
+            .enumerate()
+            .map(|(i, pair)| {
+                let count: u128 = (0..n).map(|j| transition[i][j] * initial[j]).sum();
+                (pair, count as usize)
+            })
+            .collect(),
+    })
+}
+
 fn day_14() -> Result<usize, AdventError> {
     let args: Vec<String> = env::args().collect();
     let command = args.get(1).ok_or(AdventError::NoPartArgument)?;
     let question_part = match &command[..] {
         "part-one" => Ok(QuestionPart::One),
         "part-two" => Ok(QuestionPart::Two),
+        "steps" => {
+            let arg = args.get(2).ok_or(AdventError::NoStepsArgument)?;
+            let steps = arg.parse().map_err(|_| AdventError::InvalidStepsArgument {
+                arg: arg.to_string(),
+            })?;
+            Ok(QuestionPart::Steps(steps))
+        }
         _ => Err(AdventError::InvalidCommand {
             command: args[1].to_string(),
         }),
@@ -205,10 +289,17 @@ fn day_14() -> Result<usize, AdventError> {
     let steps = match question_part {
         QuestionPart::One => 10,
         QuestionPart::Two => 40,
+        QuestionPart::Steps(steps) => steps,
     };
 
-    // Recursively find the pair counts after 10/40 steps
-    let counter = recurse(&pair_insertions, steps, counter)?;
+    // Find the pair counts after `steps` insertions. `recurse` walks one
+    // step at a time, which is fine for the 10/40 steps parts one and two
+    // ask for; beyond `MAX_LINEAR_STEPS`, switch to matrix exponentiation.
+    let counter = if steps > MAX_LINEAR_STEPS {
+        recurse_matrix(&pair_insertions, steps, counter)?
+    } else {
+        recurse(&pair_insertions, steps, counter)?
+    };
 
     // Counter of element pairs to counter of individual elements
     let element_counts = counter
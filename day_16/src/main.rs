@@ -1,5 +1,6 @@
-use bitvec::prelude::*;
 use hex::{FromHex, FromHexError};
+use num_bigint::BigUint;
+use num_traits::ToPrimitive;
 use std::env;
 use std::io::{stdin, BufRead};
 use std::process::exit;
@@ -9,6 +10,7 @@ use thiserror::Error;
 enum QuestionPart {
     One,
     Two,
+    Expression,
 }
 
 #[derive(Error, Debug)]
@@ -33,6 +35,57 @@ pub enum AdventError {
 
     #[error("Too few sub packets. Expected more sub packets during collapse.")]
     TooFewSubPackets,
+
+    #[error("Non-zero padding bit found at offset {offset}.")]
+    NonZeroPadding { offset: usize },
+}
+
+/// A cursor over a byte buffer that reads big-endian bit-fields of up to 64
+/// bits at a time, by random access rather than by splitting and
+/// re-slicing. This gives O(1) position tracking (useful for the padding
+/// check after the whole packet is parsed) and avoids recursion depth limits
+/// on pathological inputs (a literal with thousands of nibbles, or deeply
+/// nested operators).
+struct BitReader {
+    bytes: Vec<u8>,
+    bit_pos: usize,
+}
+
+impl BitReader {
+    fn new(bytes: Vec<u8>) -> Self {
+        BitReader { bytes, bit_pos: 0 }
+    }
+
+    fn total_bits(&self) -> usize {
+        self.bytes.len() * 8
+    }
+
+    /// Read the next `len` bits (big-endian, `len <= 64`) and advance the
+    /// cursor past them.
+    fn take(&mut self, len: usize) -> Result<u64, AdventError> {
+        let byte_offset = self.bit_pos / 8;
+        let first_offset = self.bit_pos % 8;
+
+        let first_byte = *self
+            .bytes
+            .get(byte_offset)
+            .ok_or(AdventError::InputEndedPrematurely)?;
+        // Mask off the bits before bit_pos, keeping the rest of the byte
+        let mut acc = (first_byte as u64) & (0xFF >> first_offset);
+        let mut accumulated = 8 - first_offset;
+
+        let mut i = byte_offset + 1;
+        while accumulated < len {
+            let byte = *self.bytes.get(i).ok_or(AdventError::InputEndedPrematurely)?;
+            acc = (acc << 8) | byte as u64;
+            accumulated += 8;
+            i += 1;
+        }
+
+        self.bit_pos += len;
+        // Discard the bits past len that got pulled in along with the last byte
+        Ok(acc >> (accumulated - len))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -48,68 +101,51 @@ enum OperatorType {
 
 #[derive(Debug, Clone)]
 enum PacketType {
-    Literal(usize),
+    Literal(u64),
     Operator(OperatorType, Vec<Packet>),
 }
 
 impl PacketType {
-    fn parse_literal(
-        bitvec: &BitSlice<Msb0, u8>,
-    ) -> Result<(&BitSlice<Msb0, u8>, PacketType), AdventError> {
+    fn parse_literal(reader: &mut BitReader) -> Result<PacketType, AdventError> {
         // Parse literal value
-        // Keep on evaluating nibbles until the first bit is zero
-        fn recurse(
-            bitvec: &BitSlice<Msb0, u8>,
-        ) -> Result<(&BitSlice<Msb0, u8>, usize, usize), AdventError> {
-            let (done, bitvec) = bitvec.split_at(1);
-            let done = !*done
-                .first()
-                .as_deref()
-                .ok_or(AdventError::InputEndedPrematurely)?;
-
-            let (current, bitvec) = bitvec.split_at(4);
-            let current = current.load_be();
-            if done {
-                return Ok((bitvec, 0, current));
+        // Keep reading 5-bit groups until one's leading bit is zero
+        let mut literal: u64 = 0;
+        loop {
+            let group = reader.take(5)?;
+            literal = (literal << 4) | (group & 0b1111);
+            if group & 0b10000 == 0 {
+                break;
             }
-            let (bitvec, i, acc) = recurse(bitvec)?;
-            let i = i + 4;
-            Ok((bitvec, i, (current << i) + acc))
         }
-
-        let (bitvec, _, literal) = recurse(bitvec)?;
-        Ok((bitvec, PacketType::Literal(literal)))
+        Ok(PacketType::Literal(literal))
     }
 
-    fn parse_operator(
-        bitvec: &BitSlice<Msb0, u8>,
-        type_id: u8,
-    ) -> Result<(&BitSlice<Msb0, u8>, PacketType), AdventError> {
+    fn parse_operator(reader: &mut BitReader, type_id: u8) -> Result<PacketType, AdventError> {
         // Parse an operator packet
         // These have many sub packets and an operator for how to collapse them
 
-        let (type_length_id, bitvec) = bitvec.split_at(1);
-        let type_length_id = *type_length_id
-            .first()
-            .as_deref()
-            .ok_or(AdventError::InputEndedPrematurely)?;
+        let length_type_id = reader.take(1)?;
 
-        let (length_type_id, bitvec) = if type_length_id {
-            // If first bit is a one, then next 11 bits are the number of sub packets
-            let (num_packets, bitvec) = bitvec.split_at(11);
-            let num_packets = num_packets.load_be::<usize>();
-            (LengthType::NumSubPackets(num_packets), bitvec)
-        } else {
-            // If first bit is a zero, then next 15 bits are the number of bits making up the
+        let sub_packets = if length_type_id == 1 {
+            // If the length type id is a one, then the next 11 bits are the number of
             // sub packets
-            let (num_bits, bitvec) = bitvec.split_at(15);
-            let num_bits = num_bits.load_be::<usize>();
-            (LengthType::NumBits(bitvec.len() - num_bits), bitvec)
+            let num_packets = reader.take(11)?;
+            (0..num_packets)
+                .map(|_| Packet::from_reader(reader))
+                .collect::<Result<Vec<_>, AdventError>>()?
+        } else {
+            // If the length type id is a zero, then the next 15 bits are the number of
+            // bits making up the sub packets
+            let num_bits = reader.take(15)? as usize;
+            let target_bit_pos = reader.bit_pos + num_bits;
+
+            let mut sub_packets = Vec::new();
+            while reader.bit_pos < target_bit_pos {
+                sub_packets.push(Packet::from_reader(reader)?);
+            }
+            sub_packets
         };
 
-        // Parse the sub packets
-        let (bitvec, sub_packets) = Packet::parse_subpackets(bitvec, length_type_id)?;
-
         // Get the appropriate operator
         let operator = PacketType::Operator(
             match type_id {
@@ -125,103 +161,88 @@ impl PacketType {
             sub_packets,
         );
 
-        Ok((bitvec, operator))
+        Ok(operator)
     }
 }
 
-enum LengthType {
-    NumBits(usize),
-    NumSubPackets(usize),
-}
-
 #[derive(Debug, Clone)]
 struct Packet {
     version: u8,
     type_id: PacketType,
+    bits_used: usize,
 }
 
 impl Packet {
     fn from_str(input: &str) -> Result<Packet, AdventError> {
         // Parse hex string into packet
-        let bitvec = BitVec::<Msb0, _>::from_vec(Vec::<u8>::from_hex(input)?);
-        let (_bitvec, packet) = Packet::from_bitvec(&bitvec)?;
+        let mut reader = BitReader::new(Vec::<u8>::from_hex(input)?);
+        let packet = Packet::from_reader(&mut reader)?;
+
+        // The BITS spec pads the transmission with zero bits up to the next
+        // byte boundary. Checking that the padding really is zero (rather
+        // than silently ignoring it) turns this into a strict parser: junk
+        // appended after a well-formed packet is rejected instead of being
+        // swallowed.
+        let offset = reader.bit_pos;
+        let padding_len = (8 - offset % 8) % 8;
+        if padding_len > 0 && reader.take(padding_len)? != 0 {
+            return Err(AdventError::NonZeroPadding { offset });
+        }
+
         Ok(packet)
     }
 
-    fn from_bitvec(
-        bitvec: &BitSlice<Msb0, u8>,
-    ) -> Result<(&BitSlice<Msb0, u8>, Packet), AdventError> {
-        // Parse bitvec into packet
+    fn from_reader(reader: &mut BitReader) -> Result<Packet, AdventError> {
+        let start = reader.bit_pos;
 
         // First three bits are version number
-        let (version, bitvec) = bitvec.split_at(3);
-        let version = version.load_be::<u8>();
+        let version = reader.take(3)? as u8;
 
         // Next three bits are type id
-        let (type_id, bitvec) = bitvec.split_at(3);
-        let type_id = type_id.load_be::<u8>();
-        let (bitvec, type_id) = match type_id {
-            4 => PacketType::parse_literal(bitvec)?,
-            _ => PacketType::parse_operator(bitvec, type_id)?,
+        let type_id = reader.take(3)? as u8;
+        let type_id = match type_id {
+            4 => PacketType::parse_literal(reader)?,
+            _ => PacketType::parse_operator(reader, type_id)?,
         };
 
-        Ok((bitvec, Packet { type_id, version }))
+        Ok(Packet {
+            type_id,
+            version,
+            bits_used: reader.bit_pos - start,
+        })
     }
 
-    fn parse_subpackets(
-        bitvec: &BitSlice<Msb0, u8>,
-        length_type_id: LengthType,
-    ) -> Result<(&BitSlice<Msb0, u8>, Vec<Packet>), AdventError> {
-        // Recursively get sub packets until we've reached the
-        // 1. number of bits (length type id = 0)
-        // 2. number of sub packets (length type id = 1)
-
-        fn recurse(
-            bitvec: &BitSlice<Msb0, u8>,
-            length_type_id: LengthType,
-            depth: usize,
-        ) -> Result<(&BitSlice<Msb0, u8>, Vec<Packet>), AdventError> {
-            match length_type_id {
-                // Stop if we've reached the number of bits
-                LengthType::NumBits(target_length) if bitvec.len() <= target_length => {
-                    Ok((bitvec, Vec::new()))
-                }
-                // Stop if we've reached the number of sub packets
-                LengthType::NumSubPackets(num_packets) if num_packets == depth => {
-                    Ok((bitvec, Vec::new()))
-                }
-                // Otherwise, recurse
-                _ => {
-                    let (bitvec, packet) = Packet::from_bitvec(bitvec)?;
-                    let (bitvec, packets) = recurse(bitvec, length_type_id, depth + 1)?;
-                    Ok((bitvec, vec![packet].into_iter().chain(packets).collect()))
-                }
-            }
-        }
-        recurse(bitvec, length_type_id, 0)
+    /// Number of bits this packet (including all of its sub packets)
+    /// occupied in the transmission, so callers can re-serialize it or skip
+    /// past it to read a stream of back-to-back packets.
+    fn bits_used(&self) -> usize {
+        self.bits_used
     }
 
-    fn collapse(self) -> Result<usize, AdventError> {
-        // Collapse a packet down to a single value
+    fn collapse(self) -> Result<BigUint, AdventError> {
+        // Collapse a packet down to a single value. Evaluated over `BigUint` rather than a fixed-
+        // width integer, since a long enough chain of `Product` operators can exceed `u64` on
+        // adversarial (or just very deeply nested) input; growing the number instead of wrapping
+        // or erroring is what arbitrary-precision arithmetic buys us here.
         match self.type_id {
-            PacketType::Literal(x) => Ok(x),
+            PacketType::Literal(x) => Ok(BigUint::from(x)),
             PacketType::Operator(operator_type, sub_packets) => {
-                let operator = match operator_type {
-                    OperatorType::Sum => |x, y| x + y,
-                    OperatorType::Product => |x, y| x * y,
-                    OperatorType::Minimum => std::cmp::min,
-                    OperatorType::Maximum => std::cmp::max,
-                    OperatorType::GreaterThan => |x, y| if x > y { 1 } else { 0 },
-                    OperatorType::LessThan => |x, y| if x < y { 1 } else { 0 },
-                    OperatorType::EqualTo => |x, y| if x == y { 1 } else { 0 },
-                };
-                sub_packets
+                let mut values = sub_packets
                     .into_iter()
-                    .map(|p| p.collapse())
+                    .map(Packet::collapse)
                     .collect::<Result<Vec<_>, AdventError>>()?
-                    .into_iter()
-                    .reduce(operator)
-                    .ok_or(AdventError::TooFewSubPackets)
+                    .into_iter();
+                let first = values.next().ok_or(AdventError::TooFewSubPackets)?;
+
+                Ok(values.fold(first, |acc, x| match operator_type {
+                    OperatorType::Sum => acc + x,
+                    OperatorType::Product => acc * x,
+                    OperatorType::Minimum => std::cmp::min(acc, x),
+                    OperatorType::Maximum => std::cmp::max(acc, x),
+                    OperatorType::GreaterThan => BigUint::from(u8::from(acc > x)),
+                    OperatorType::LessThan => BigUint::from(u8::from(acc < x)),
+                    OperatorType::EqualTo => BigUint::from(u8::from(acc == x)),
+                }))
             }
         }
     }
@@ -239,14 +260,73 @@ impl Packet {
             }
         }
     }
+
+    fn to_expression(&self) -> String {
+        // Render the packet tree as a human-readable math expression, mostly
+        // useful for debugging why `collapse` returned an unexpected value
+        let mut expression = String::new();
+        self.write_expression(&mut expression)
+            .expect("writing to a String can't fail");
+        expression
+    }
+
+    fn write_expression(&self, out: &mut String) -> std::fmt::Result {
+        use std::fmt::Write;
+
+        match &self.type_id {
+            PacketType::Literal(x) => write!(out, "{x}"),
+            PacketType::Operator(OperatorType::Minimum, sub_packets) => {
+                write!(out, "min(")?;
+                Packet::write_expression_list(sub_packets, out)?;
+                write!(out, ")")
+            }
+            PacketType::Operator(OperatorType::Maximum, sub_packets) => {
+                write!(out, "max(")?;
+                Packet::write_expression_list(sub_packets, out)?;
+                write!(out, ")")
+            }
+            PacketType::Operator(operator_type, sub_packets) => {
+                let symbol = match operator_type {
+                    OperatorType::Sum => "+",
+                    OperatorType::Product => "*",
+                    OperatorType::GreaterThan => ">",
+                    OperatorType::LessThan => "<",
+                    OperatorType::EqualTo => "==",
+                    OperatorType::Minimum | OperatorType::Maximum => unreachable!(),
+                };
+
+                write!(out, "(")?;
+                for (i, sub_packet) in sub_packets.iter().enumerate() {
+                    if i > 0 {
+                        write!(out, " {symbol} ")?;
+                    }
+                    sub_packet.write_expression(out)?;
+                }
+                write!(out, ")")
+            }
+        }
+    }
+
+    fn write_expression_list(sub_packets: &[Packet], out: &mut String) -> std::fmt::Result {
+        use std::fmt::Write;
+
+        for (i, sub_packet) in sub_packets.iter().enumerate() {
+            if i > 0 {
+                write!(out, ", ")?;
+            }
+            sub_packet.write_expression(out)?;
+        }
+        Ok(())
+    }
 }
 
-fn day_16() -> Result<usize, AdventError> {
+fn day_16() -> Result<String, AdventError> {
     let args: Vec<String> = env::args().collect();
     let command = args.get(1).ok_or(AdventError::NoPartArgument)?;
     let question_part = match &command[..] {
         "part-one" => Ok(QuestionPart::One),
         "part-two" => Ok(QuestionPart::Two),
+        "expression" => Ok(QuestionPart::Expression),
         _ => Err(AdventError::InvalidCommand {
             command: args[1].to_string(),
         }),
@@ -258,8 +338,9 @@ fn day_16() -> Result<usize, AdventError> {
     let packet = Packet::from_str(input)?;
 
     Ok(match question_part {
-        QuestionPart::One => packet.add_versions(),
-        QuestionPart::Two => packet.collapse()?,
+        QuestionPart::One => packet.add_versions().to_string(),
+        QuestionPart::Two => packet.collapse()?.to_string(),
+        QuestionPart::Expression => packet.to_expression(),
     })
 }
 
@@ -273,5 +354,7 @@ fn main() {
     }
 }
 
+mod nom_parser;
+
 #[cfg(test)]
 mod tests;
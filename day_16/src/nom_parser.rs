@@ -0,0 +1,125 @@
+//! An alternative BITS parser built on `nom`'s bit combinators instead of
+//! the hand-rolled `BitReader` cursor in `main.rs`. It produces the same
+//! `Packet`/`PacketType` tree, so `collapse`, `add_versions`, and
+//! `to_expression` all work unchanged on the result - only how the bits get
+//! turned into a tree differs. Kept side-by-side with the cursor backend as
+//! a reference for how the grammar reads as composable sub-parsers with
+//! precise error positions, rather than as evidence one approach beats the
+//! other.
+
+use hex::FromHex;
+use nom::bits::complete::take;
+use nom::combinator::{flat_map, map};
+use nom::multi::many_m_n;
+use nom::sequence::pair;
+use nom::IResult;
+
+use super::{AdventError, OperatorType, Packet, PacketType};
+
+/// `nom::bits` input: the remaining bytes, paired with a bit offset into
+/// the first of them.
+type BitInput<'a> = (&'a [u8], usize);
+
+fn remaining_bits((bytes, offset): &BitInput) -> usize {
+    bytes.len() * 8 - offset
+}
+
+/// Parse a hex-encoded transmission with the `nom` backend, for comparison
+/// against [`Packet::from_str`].
+#[allow(dead_code)]
+pub(crate) fn parse_hex(input: &str) -> Result<Packet, AdventError> {
+    let bytes = Vec::<u8>::from_hex(input)?;
+    let (_, packet) =
+        Packet::parse((&bytes, 0)).map_err(|_| AdventError::InputEndedPrematurely)?;
+    Ok(packet)
+}
+
+impl Packet {
+    pub(crate) fn parse(input: BitInput) -> IResult<BitInput, Packet> {
+        let start = remaining_bits(&input);
+        let (input, version): (_, u8) = take(3usize)(input)?;
+        let (input, type_id) = flat_map(take(3usize), PacketType::parse)(input)?;
+        let bits_used = start - remaining_bits(&input);
+
+        Ok((
+            input,
+            Packet {
+                version,
+                type_id,
+                bits_used,
+            },
+        ))
+    }
+}
+
+impl PacketType {
+    fn parse(type_id: u8) -> impl FnMut(BitInput) -> IResult<BitInput, PacketType> {
+        move |input| match type_id {
+            4 => Self::parse_literal(input),
+            _ => Self::parse_operator(type_id, input),
+        }
+    }
+
+    fn parse_literal(input: BitInput) -> IResult<BitInput, PacketType> {
+        let mut value: u64 = 0;
+        let mut input = input;
+        loop {
+            let (rest, (more, nibble)): (_, (bool, u8)) =
+                pair(map(take(1usize), |bit: u8| bit == 1), take(4usize))(input)?;
+            value = (value << 4) | nibble as u64;
+            input = rest;
+            if !more {
+                break;
+            }
+        }
+        Ok((input, PacketType::Literal(value)))
+    }
+
+    fn parse_operator(type_id: u8, input: BitInput) -> IResult<BitInput, PacketType> {
+        let (input, length_type_id): (_, u8) = take(1usize)(input)?;
+
+        let (input, sub_packets) = if length_type_id == 1 {
+            flat_map(take(11usize), |count: u16| {
+                many_m_n(count as usize, count as usize, Packet::parse)
+            })(input)?
+        } else {
+            let (input, num_bits): (_, usize) = take(15usize)(input)?;
+            take_packets_within(num_bits)(input)?
+        };
+
+        let operator = match type_id {
+            0 => OperatorType::Sum,
+            1 => OperatorType::Product,
+            2 => OperatorType::Minimum,
+            3 => OperatorType::Maximum,
+            5 => OperatorType::GreaterThan,
+            6 => OperatorType::LessThan,
+            7 => OperatorType::EqualTo,
+            // `take(3usize)` only ever yields 0..=7, and 4 is handled by
+            // `parse_literal`, so every remaining value is a valid operator.
+            _ => unreachable!("invalid type id {type_id}"),
+        };
+
+        Ok((input, PacketType::Operator(operator, sub_packets)))
+    }
+}
+
+/// Fold `Packet::parse` over `input` until `num_bits` worth of sub packets
+/// have been consumed, the bit-length-mode counterpart to `many_m_n` above.
+fn take_packets_within(num_bits: usize) -> impl FnMut(BitInput) -> IResult<BitInput, Vec<Packet>> {
+    move |input: BitInput| {
+        let mut remaining = num_bits;
+        let mut input = input;
+        let mut sub_packets = Vec::new();
+
+        while remaining > 0 {
+            let before = remaining_bits(&input);
+            let (rest, packet) = Packet::parse(input)?;
+            remaining -= before - remaining_bits(&rest);
+            sub_packets.push(packet);
+            input = rest;
+        }
+
+        Ok((input, sub_packets))
+    }
+}
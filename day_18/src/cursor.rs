@@ -0,0 +1,158 @@
+//! A zipper/cursor over [`Node`], in the spirit of the cursor trees used by
+//! IDE parsers (rowan et al.): a focused subtree plus the path of frames
+//! back up to the root. Moving the focus touches only the nodes on that
+//! path, not the whole tree, so `explode` can step to the adjacent leaf in
+//! in-order traversal without reconstructing anything it doesn't visit.
+//! Nothing here is specific to snailfish numbers - it's a general
+//! "leaf-to-leaf navigation" API for any binary tree shaped like `Node`.
+
+use super::{Node, ParentNode};
+
+/// Which child of its parent a focused subtree was.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Side {
+    Left,
+    Right,
+}
+
+/// One step up from a focused subtree: the side it was on, and the sibling
+/// it was paired with.
+#[derive(Debug, Clone)]
+struct Frame {
+    side: Side,
+    sibling: Node,
+}
+
+/// A cursor into a `Node` tree. `Ok`/`Err` on the movement methods carries
+/// the cursor back either way, so a failed move (there's no such neighbour)
+/// never loses the caller's position.
+#[derive(Debug)]
+pub(crate) struct Cursor {
+    focus: Node,
+    path: Vec<Frame>,
+}
+
+impl Cursor {
+    pub(crate) fn new(focus: Node) -> Self {
+        Cursor {
+            focus,
+            path: Vec::new(),
+        }
+    }
+
+    pub(crate) fn focus(&self) -> &Node {
+        &self.focus
+    }
+
+    pub(crate) fn focus_mut(&mut self) -> &mut Node {
+        &mut self.focus
+    }
+
+    pub(crate) fn depth(&self) -> usize {
+        self.path.len()
+    }
+
+    /// Descend into the left child of a `Parent` focus, or hand the cursor
+    /// back unmoved if it's a `Leaf`.
+    pub(crate) fn down_left(mut self) -> Result<Self, Self> {
+        match self.focus {
+            Node::Parent(parent) => {
+                let ParentNode { left, right } = *parent;
+                self.path.push(Frame {
+                    side: Side::Left,
+                    sibling: right,
+                });
+                self.focus = left;
+                Ok(self)
+            }
+            Node::Leaf(_) => Err(self),
+        }
+    }
+
+    /// Descend into the right child of a `Parent` focus, or hand the cursor
+    /// back unmoved if it's a `Leaf`.
+    pub(crate) fn down_right(mut self) -> Result<Self, Self> {
+        match self.focus {
+            Node::Parent(parent) => {
+                let ParentNode { left, right } = *parent;
+                self.path.push(Frame {
+                    side: Side::Right,
+                    sibling: left,
+                });
+                self.focus = right;
+                Ok(self)
+            }
+            Node::Leaf(_) => Err(self),
+        }
+    }
+
+    /// Step back up to the parent of the focus, reattaching the sibling
+    /// that was set aside on the way down. Fails only at the root.
+    pub(crate) fn up(mut self) -> Result<Self, Self> {
+        let Some(frame) = self.path.pop() else {
+            return Err(self);
+        };
+        self.focus = match frame.side {
+            Side::Left => ParentNode {
+                left: self.focus,
+                right: frame.sibling,
+            },
+            Side::Right => ParentNode {
+                left: frame.sibling,
+                right: self.focus,
+            },
+        }
+        .node();
+        Ok(self)
+    }
+
+    /// Move to the leaf immediately before the focus in in-order traversal,
+    /// by climbing until there's a left sibling to descend into, then
+    /// taking its rightmost leaf. Fails if the focus is the first leaf.
+    pub(crate) fn move_prev_leaf(mut self) -> Result<Self, Self> {
+        while matches!(self.path.last(), Some(frame) if frame.side == Side::Left) {
+            self = self.up()?;
+        }
+        if self.path.is_empty() {
+            return Err(self);
+        }
+
+        let mut cursor = self.up()?.down_left()?;
+        loop {
+            cursor = match cursor.down_right() {
+                Ok(next) => next,
+                Err(leaf) => return Ok(leaf),
+            };
+        }
+    }
+
+    /// Move to the leaf immediately after the focus in in-order traversal,
+    /// the mirror image of `move_prev_leaf`.
+    pub(crate) fn move_next_leaf(mut self) -> Result<Self, Self> {
+        while matches!(self.path.last(), Some(frame) if frame.side == Side::Right) {
+            self = self.up()?;
+        }
+        if self.path.is_empty() {
+            return Err(self);
+        }
+
+        let mut cursor = self.up()?.down_right()?;
+        loop {
+            cursor = match cursor.down_left() {
+                Ok(next) => next,
+                Err(leaf) => return Ok(leaf),
+            };
+        }
+    }
+
+    /// Walk back up to the root, reattaching every sibling along the way,
+    /// and return the whole tree.
+    pub(crate) fn rebuild(mut self) -> Node {
+        loop {
+            self = match self.up() {
+                Ok(cursor) => cursor,
+                Err(cursor) => return cursor.focus,
+            };
+        }
+    }
+}
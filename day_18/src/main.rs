@@ -1,4 +1,6 @@
 use itertools::Itertools;
+use lalrpop_util::lalrpop_mod;
+use rayon::prelude::*;
 use std::env;
 use std::fmt;
 use std::io::{stdin, BufRead};
@@ -6,6 +8,11 @@ use std::ops::Add;
 use std::process::exit;
 use thiserror::Error;
 
+lalrpop_mod!(pub grammar);
+
+mod cursor;
+use cursor::{Cursor, Side};
+
 #[derive(Debug)]
 enum QuestionPart {
     One,
@@ -20,29 +27,26 @@ pub enum AdventError {
     #[error(transparent)]
     Io(#[from] std::io::Error),
 
-    #[error("Could not parse character `{c}' to digit.")]
-    ParseInt { c: char },
-
     #[error("Please specify `part-one' or `part-two' as the first argument.")]
     NoPartArgument,
 
     #[error("Invalid input. Expected `target area: x=<x1>..<x2>, y=<y1>..<y2>'. Found `{line}'.")]
     InputError { line: String },
 
-    #[error("Failed to parse line of input. Expected `,' in substring `{haystack}'.")]
-    NoComma { haystack: String },
-
-    #[error("Failed to parse line of input. Expected substring `{haystack}' to start with `['.")]
-    NoOpenBrace { haystack: String },
-
-    #[error("Failed to parse line of input. Expected substring `{haystack}' to end with `]'.")]
-    NoCloseBrace { haystack: String },
+    #[error("Failed to parse snailfish number on line {line}, offset {offset}.")]
+    ParseError { line: usize, offset: usize },
 
     #[error("Empty input given")]
     EmptyInput,
 
     #[error("Attempted to add two parent nodes, which is prohibited.")]
     NotALeaf,
+
+    #[error("Please specify the number of jobs as the argument to `--jobs'.")]
+    NoJobsArgument,
+
+    #[error("Could not parse `{arg}' as a number of jobs.")]
+    InvalidJobsArgument { arg: String },
 }
 
 // Tree implementation
@@ -66,86 +70,153 @@ impl ParentNode {
         }
     }
 
-    fn where_left(&self, left: Node) -> Self {
-        // Get a new node where left is given by the argument
-        Self {
-            left,
-            right: self.right.clone(),
-        }
+    // Turn a parent struct into a full node on the heap
+    fn node(self) -> Node {
+        Node::Parent(Box::new(self))
+    }
+}
+
+impl Node {
+    /// Reduce a snail number in place until it doesn't need to explode or
+    /// be split.
+    pub fn reduce(&mut self) {
+        reduce_with(self, &[Box::new(ExplodeRule), Box::new(SplitRule)]);
     }
 
-    fn where_right(&self, right: Node) -> Self {
-        // Get a new node where right is given by the argument
-        Self {
-            right,
-            left: self.left.clone(),
+    /// Explode the first eligible pair (a `Parent` of two `Leaf`s at
+    /// depth >= 4), if any. Returns whether one exploded.
+    ///
+    /// Finds the pair with a plain recursive search, then drives a
+    /// `Cursor` to it and uses `move_prev_leaf`/`move_next_leaf` to find
+    /// its neighbour leaves, rather than propagating the carry back up
+    /// through the tree as it unwinds.
+    fn explode(&mut self) -> bool {
+        let Some(path) = Node::find_exploding_pair(self, 0) else {
+            return false;
+        };
+
+        let mut cursor = Cursor::new(std::mem::replace(self, Node::Leaf(0)));
+        for side in path {
+            cursor = match side {
+                Side::Left => cursor.down_left(),
+                Side::Right => cursor.down_right(),
+            }
+            .unwrap_or_else(|_| panic!("find_exploding_pair's path always resolves"));
         }
-    }
 
-    fn zero_right(&self) -> Self {
-        // Get a new node with zero as a right leaf
-        self.where_right(Node::Leaf(0))
-    }
+        let (left, right) = match cursor.focus() {
+            Node::Parent(parent) => match (&parent.left, &parent.right) {
+                (Node::Leaf(left), Node::Leaf(right)) => (*left, *right),
+                _ => unreachable!("find_exploding_pair only returns paths to leaf pairs"),
+            },
+            Node::Leaf(_) => unreachable!("find_exploding_pair only returns paths to parents"),
+        };
+        *cursor.focus_mut() = Node::Leaf(0);
+
+        let cursor = match cursor.move_prev_leaf() {
+            Ok(mut prev) => {
+                if let Node::Leaf(x) = prev.focus_mut() {
+                    *x += left;
+                }
+                prev.move_next_leaf()
+                    .unwrap_or_else(|_| panic!("the leaf just exploded is always the successor"))
+            }
+            Err(cursor) => cursor,
+        };
+        let cursor = match cursor.move_next_leaf() {
+            Ok(mut next) => {
+                if let Node::Leaf(x) = next.focus_mut() {
+                    *x += right;
+                }
+                next.move_prev_leaf()
+                    .unwrap_or_else(|_| panic!("the leaf just exploded is always the predecessor"))
+            }
+            Err(cursor) => cursor,
+        };
 
-    fn zero_left(&self) -> Self {
-        // Get a new node with zero as a left leaf
-        self.where_left(Node::Leaf(0))
+        *self = cursor.rebuild();
+        true
     }
 
-    // When we add to the left or right,
-    // we want the number to trickle down on the opposite side until a leaf is hit
-    fn add_left(self, other: &Node) -> Self {
-        match other {
-            Node::Leaf(other) => Self {
-                right: self.right,
-                left: self.left.trickle_right(*other),
-            },
-            Node::Parent(_) => panic!("Cannot add parent node to a node."),
+    /// Find the path (from the root) to the first `Parent` of two `Leaf`s
+    /// at depth >= 4, in pre-order.
+    fn find_exploding_pair(node: &Node, depth: usize) -> Option<Vec<Side>> {
+        let Node::Parent(parent) = node else {
+            return None;
+        };
+
+        if depth >= 4 && matches!((&parent.left, &parent.right), (Node::Leaf(_), Node::Leaf(_))) {
+            return Some(Vec::new());
         }
-    }
 
-    fn add_right(self, other: &Node) -> Self {
-        match other {
-            Node::Leaf(other) => Self {
-                left: self.left,
-                right: self.right.trickle_left(*other),
-            },
-            Node::Parent(_) => panic!("Cannot add parent node to a node."),
+        if let Some(mut path) = Node::find_exploding_pair(&parent.left, depth + 1) {
+            path.insert(0, Side::Left);
+            return Some(path);
+        }
+        if let Some(mut path) = Node::find_exploding_pair(&parent.right, depth + 1) {
+            path.insert(0, Side::Right);
+            return Some(path);
         }
+        None
     }
 
-    // Turn a parent struct into a full node on the heap
-    fn node(self) -> Node {
-        Node::Parent(Box::new(self))
+    /// Split the first leaf greater than 9 into a pair, in place. Returns
+    /// whether a leaf was split.
+    fn split(&mut self) -> bool {
+        if let Node::Leaf(x) = self {
+            if *x <= 9 {
+                return false;
+            }
+            *self = ParentNode {
+                left: Node::Leaf(*x / 2),
+                right: Node::Leaf(*x / 2 + *x % 2),
+            }
+            .node();
+            return true;
+        }
+
+        let Node::Parent(parent) = self else {
+            unreachable!()
+        };
+        parent.left.split() || parent.right.split()
     }
 }
 
-impl Node {
-    // When we add to the left or right,
-    // we want the number to trickle down on the opposite side until a leaf is hit
-    fn trickle_right(self, other: u8) -> Self {
-        match self {
-            Node::Parent(parent) => ParentNode {
-                left: parent.left,
-                right: parent.right.trickle_right(other),
-            }
-            .node(),
-            Node::Leaf(leaf) => Node::Leaf(leaf + other),
-        }
+/// A single rewrite rule in a term-rewriting reduction: given the node to
+/// reduce, mutate it in place and report whether the rule fired.
+trait Rule {
+    fn try_apply(&self, node: &mut Node) -> bool;
+}
+
+/// The built-in explode rule, wrapping [`Node::explode`].
+struct ExplodeRule;
+
+impl Rule for ExplodeRule {
+    fn try_apply(&self, node: &mut Node) -> bool {
+        node.explode()
     }
+}
 
-    fn trickle_left(self, other: u8) -> Self {
-        match self {
-            Node::Parent(parent) => ParentNode {
-                right: parent.right,
-                left: parent.left.trickle_left(other),
-            }
-            .node(),
-            Node::Leaf(leaf) => Node::Leaf(leaf + other),
-        }
+/// The built-in split rule, wrapping [`Node::split`].
+struct SplitRule;
+
+impl Rule for SplitRule {
+    fn try_apply(&self, node: &mut Node) -> bool {
+        node.split()
     }
 }
 
+/// Drive a fixpoint reduction: scan `rules` in priority order and, as soon
+/// as one fires, restart the scan from the top, until a full pass finds
+/// nothing left to apply. This is what the snailfish rules require - a
+/// successful explode must be retried before any split is attempted - but
+/// the driver itself knows nothing about exploding or splitting, so new
+/// rules (a different explode depth, a different split threshold, a
+/// "round down" split) can be dropped in without touching it.
+fn reduce_with(node: &mut Node, rules: &[Box<dyn Rule>]) {
+    while rules.iter().any(|rule| rule.try_apply(node)) {}
+}
+
 impl fmt::Display for Node {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -170,147 +241,39 @@ impl Add<u8> for Node {
     }
 }
 
-fn find_comma(input: &[char]) -> Option<usize> {
-    // Find the comma that separates a pair
-    // Move through the string until the number of [ and ] seen are equal
-    // and the current character is a comma
-
-    fn recurse(i: usize, depth: usize, input: &[char]) -> Option<usize> {
-        match input.get(i) {
-            Some(c) => match c {
-                ',' if depth == 0 => Some(i),
-                '[' => recurse(i + 1, depth + 1, input),
-                ']' => recurse(i + 1, depth - 1, input),
-                _ => recurse(i + 1, depth, input),
-            },
-            None => None,
-        }
-    }
-
-    recurse(0, 0, input)
-}
-
-fn parse(line: &[char]) -> Result<Node, AdventError> {
-    // Parse one line of input (one snail number)
-
-    // Base case
-    if line.len() == 1 {
-        let c = line[0];
-        Ok(Node::Leaf(
-            c.to_digit(10).ok_or(AdventError::ParseInt { c })? as u8,
-        ))
-    } else {
-        // Ensure start and end are braces
-        if line.first() != Some(&'[') {
-            return Err(AdventError::NoOpenBrace {
-                haystack: line.iter().collect(),
-            });
-        }
-        if line.last() != Some(&']') {
-            return Err(AdventError::NoCloseBrace {
-                haystack: line.iter().collect(),
-            });
-        }
-
-        // Remove start and end braces
-        let line = &line[1..line.len() - 1];
-
-        // Split on the comma
-        let split = find_comma(line).ok_or(AdventError::NoComma {
-            haystack: line.iter().collect(),
-        })?;
-
-        Ok(ParentNode {
-            left: parse(&line[..split])?,
-            right: parse(&line[split + 1..])?,
-        }
-        .node())
-    }
-}
-
-fn parse_str(line: &str) -> Result<Node, AdventError> {
-    parse(&line.chars().collect::<Vec<_>>())
-}
-
-fn explode(node: &Node, depth: usize) -> Result<Node, (Node, ParentNode)> {
-    // Explode if a number is eligible
-    // Returns Ok(head) if the number does not explode,
-    // and Err(head, _) otherwise after propagating the explosion
-    // The other argument is a { left, right} with the number that should be added on each side
-    // While unwinding, if we find an aunt or uncle on that side, add this then zero it
-
-    match node {
-        Node::Parent(parent) => {
-            // If depth is 4 and we're a parent, explode
-            // When we explode, we become zero and our left and right bubble up
-            if depth == 4 {
-                return Err((Node::Leaf(0), *parent.clone()));
-            }
-
-            // Otherwise, recurse left and right
-            // If left explodes, bubble right
-            // If right explodes, bubble left
-            Ok(ParentNode {
-                left: explode(&parent.left, depth + 1).map_err(|(tree, other)| {
-                    (
-                        parent.where_left(tree).add_right(&other.right).node(),
-                        other.zero_right(),
-                    )
-                })?,
-                right: explode(&parent.right, depth + 1).map_err(|(tree, other)| {
-                    (
-                        parent.where_right(tree).add_left(&other.left).node(),
-                        other.zero_left(),
-                    )
-                })?,
-            }
-            .node())
-        }
-        Node::Leaf(_) => Ok(node.to_owned()),
-    }
-}
-
-fn split(node: &Node) -> Result<Node, Node> {
-    // Recursively check for values that must be split
-    match node {
-        Node::Leaf(x) => {
-            // Any value greater than 9 has to become a pair of floor(9/2) and ceil(9/2)
-            if x > &9 {
-                Err(ParentNode {
-                    left: Node::Leaf(x / 2),
-                    right: Node::Leaf(x / 2 + x % 2),
-                }
-                .node())
-            } else {
-                Ok(node.to_owned())
-            }
-        }
-        Node::Parent(parent) => Ok(ParentNode {
-            left: split(&parent.left).map_err(|tree| parent.where_left(tree).node())?,
-            right: split(&parent.right).map_err(|tree| parent.where_right(tree).node())?,
-        }
-        .node()),
+/// Pull a byte offset out of a LALRPOP parse error, regardless of which
+/// variant it failed with, so callers can point at the exact malformed
+/// token instead of echoing the whole line.
+fn parse_error_offset(
+    error: &lalrpop_util::ParseError<usize, grammar::Token<'_>, &'static str>,
+) -> usize {
+    use lalrpop_util::ParseError::*;
+    match error {
+        InvalidToken { location } => *location,
+        UnrecognizedEof { location, .. } => *location,
+        UnrecognizedToken { token: (start, ..), .. } => *start,
+        ExtraToken { token: (start, ..) } => *start,
+        User { .. } => 0,
     }
 }
 
-fn reduce(node: Node) -> Node {
-    // Reduce a snail number until it doesn't need to explode or be split
-    let node = explode(&node, 0).unwrap_or_else(|(tree, _)| {
-        // println!("Explode: {}", tree);
-        reduce(tree)
-    });
-    split(&node).unwrap_or_else(|tree| {
-        // println!("Split: {}", tree);
-        reduce(tree)
-    })
+fn parse_str(line_number: usize, line: &str) -> Result<Node, AdventError> {
+    // Parse one line of input (one snail number) with the generated
+    // `Tree` grammar instead of hand-rolled recursion over chars.
+    grammar::TreeParser::new()
+        .parse(line)
+        .map_err(|err| AdventError::ParseError {
+            line: line_number,
+            offset: parse_error_offset(&err),
+        })
 }
 
-fn magnitude(node: Node) -> usize {
+fn magnitude(node: &Node) -> usize {
     // Get the magnitude of a snail number
     // It's 3 times the left and 2 times the right
     match node {
-        Node::Parent(parent) => 3 * magnitude(parent.left) + 2 * magnitude(parent.right),
-        Node::Leaf(x) => x as usize,
+        Node::Parent(parent) => 3 * magnitude(&parent.left) + 2 * magnitude(&parent.right),
+        Node::Leaf(x) => *x as usize,
     }
 }
 
@@ -325,13 +288,27 @@ fn day_18() -> Result<usize, AdventError> {
         }),
     }?;
 
+    // Optional `--jobs N` argument: part two reduces every permutation of
+    // two numbers independently, so above one job it's split across a
+    // rayon thread pool instead of walked sequentially.
+    let jobs = match args.get(2).map(String::as_str) {
+        Some("--jobs") => {
+            let arg = args.get(3).ok_or(AdventError::NoJobsArgument)?;
+            arg.parse().map_err(|_| AdventError::InvalidJobsArgument {
+                arg: arg.to_string(),
+            })?
+        }
+        _ => 1,
+    };
+
     let lines = stdin()
         .lock()
         .lines()
         .collect::<Result<Vec<String>, std::io::Error>>()?;
     let numbers = lines
         .iter()
-        .map(|line| parse_str(line))
+        .enumerate()
+        .map(|(i, line)| parse_str(i + 1, line))
         .collect::<Result<Vec<_>, AdventError>>()?;
 
     Ok(match question_part {
@@ -339,25 +316,38 @@ fn day_18() -> Result<usize, AdventError> {
             // In part one, get the magnitude of the sum of the numbers in the input
             let sum = numbers
                 .into_iter()
-                .reduce(|left, right| reduce(ParentNode { left, right }.node()))
+                .reduce(|left, right| {
+                    let mut sum = ParentNode { left, right }.node();
+                    sum.reduce();
+                    sum
+                })
                 .ok_or(AdventError::EmptyInput)?;
-            magnitude(sum)
+            magnitude(&sum)
         }
-        QuestionPart::Two => numbers
+        QuestionPart::Two => {
             // In part two, get the maximum sum of any two numbers in the input
-            .into_iter()
-            .permutations(2)
-            .map(|items| {
-                let node = ParentNode {
+            let reduce_pair = |items: &Vec<Node>| {
+                let mut node = ParentNode {
                     left: items[0].clone(),
                     right: items[1].clone(),
                 }
                 .node();
-                let node = reduce(node);
-                magnitude(node)
-            })
-            .max()
-            .ok_or(AdventError::EmptyInput)?,
+                node.reduce();
+                magnitude(&node)
+            };
+
+            let permutations: Vec<Vec<Node>> = numbers.into_iter().permutations(2).collect();
+            if jobs > 1 {
+                let pool = rayon::ThreadPoolBuilder::new()
+                    .num_threads(jobs)
+                    .build()
+                    .expect("failed to build thread pool");
+                pool.install(|| permutations.par_iter().map(reduce_pair).max())
+            } else {
+                permutations.iter().map(reduce_pair).max()
+            }
+            .ok_or(AdventError::EmptyInput)?
+        }
     })
 }
 
@@ -377,52 +367,73 @@ mod tests {
 
     #[test]
     fn test_explode() -> Result<(), AdventError> {
-        for (before, after, (left, right)) in [
-            ("[[[[[9,8],1],2],3],4]", "[[[[0,9],2],3],4]", (9, 0)),
-            ("[7,[6,[5,[4,[3,2]]]]]", "[7,[6,[5,[7,0]]]]", (0, 2)),
-            ("[[6,[5,[4,[3,2]]]],1]", "[[6,[5,[7,0]]],3]", (0, 0)),
+        for (before, after) in [
+            ("[[[[[9,8],1],2],3],4]", "[[[[0,9],2],3],4]"),
+            ("[7,[6,[5,[4,[3,2]]]]]", "[7,[6,[5,[7,0]]]]"),
+            ("[[6,[5,[4,[3,2]]]],1]", "[[6,[5,[7,0]]],3]"),
             (
                 "[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]",
                 "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]",
-                (0, 0),
             ),
             (
                 "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]",
                 "[[3,[2,[8,0]]],[9,[5,[7,0]]]]",
-                (0, 2),
             ),
         ] {
-            let expected_tree = parse_str(after)?;
-            let expected_extra = ParentNode::new(left, right);
-            match explode(&parse_str(before)?, 0) {
-                Ok(_) => panic!("{} did not explode", before),
-                Err((tree, extra)) => {
-                    if tree != expected_tree {
-                        panic!("Expected {}, found {}", expected_tree, tree);
-                    }
-                    if extra != expected_extra {
-                        panic!("Expected {:?}, found {:?}", expected_extra, extra);
-                    }
-                }
-            }
+            let mut node = parse_str(1, before)?;
+            assert!(node.explode(), "{} did not explode", before);
+            let expected = parse_str(1, after)?;
+            assert_eq!(node, expected, "exploding {}", before);
         }
         Ok(())
     }
 
     #[test]
     fn test_add() -> Result<(), AdventError> {
+        let mut sum = parse_str(
+            1,
+            "[[[[0,[4,5]],[0,0]],[[[4,5],[2,6]],[9,5]]],[7,[[[3,7],[4,3]],[[6,3],[8,8]]]]]",
+        )?;
+        sum.reduce();
         assert_eq!(
-            reduce(parse_str(
-                "[[[[0,[4,5]],[0,0]],[[[4,5],[2,6]],[9,5]]],[7,[[[3,7],[4,3]],[[6,3],[8,8]]]]]"
-            )?),
-            parse_str("[[[[4,0],[5,4]],[[7,7],[6,0]]],[[8,[7,7]],[[7,9],[5,0]]]]")?,
+            sum,
+            parse_str(1, "[[[[4,0],[5,4]],[[7,7],[6,0]]],[[8,[7,7]],[[7,9],[5,0]]]]")?,
         );
         Ok(())
     }
 
     #[test]
     fn test_magnitude() -> Result<(), AdventError> {
-        assert_eq!(magnitude(parse_str("[9,1]")?), 29);
+        assert_eq!(magnitude(&parse_str(1, "[9,1]")?), 29);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_error_points_at_malformed_token() {
+        // Missing the closing `]`: the grammar should fail right where the
+        // comma is found but no matching bracket follows, not on the whole
+        // line.
+        assert!(matches!(
+            parse_str(3, "[1,2"),
+            Err(AdventError::ParseError { line: 3, offset: 4 })
+        ));
+    }
+
+    #[test]
+    fn test_reduce_with_accepts_custom_rules() -> Result<(), AdventError> {
+        // Swap in a rule that never fires in place of `SplitRule`: with no
+        // pair nested 4 deep to explode either, `reduce_with` should leave
+        // the tree untouched even though it has a leaf greater than 9.
+        struct NeverSplitRule;
+        impl Rule for NeverSplitRule {
+            fn try_apply(&self, _node: &mut Node) -> bool {
+                false
+            }
+        }
+
+        let mut node = parse_str(1, "[11,1]")?;
+        reduce_with(&mut node, &[Box::new(ExplodeRule), Box::new(NeverSplitRule)]);
+        assert_eq!(node, parse_str(1, "[11,1]")?);
         Ok(())
     }
 }
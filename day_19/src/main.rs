@@ -1,7 +1,7 @@
+use common::{input, parsing};
 use itertools::iproduct;
 use itertools::Itertools;
-use lazy_static::lazy_static;
-use regex::Regex;
+use pathfinding::prelude::connected_components;
 use std::collections::{HashMap, HashSet};
 use std::env;
 use std::io::{stdin, Read};
@@ -9,6 +9,9 @@ use std::ops::{Add, Sub};
 use std::process::exit;
 use thiserror::Error;
 
+const YEAR: u16 = 2021;
+const DAY: u8 = 19;
+
 #[derive(Debug, Clone, Copy)]
 enum QuestionPart {
     One,
@@ -26,23 +29,17 @@ pub enum AdventError {
     #[error("Please specify `part-one' or `part-two' as the first argument.")]
     NoPartArgument,
 
-    #[error("Could not parse `{input}' to int.")]
-    ParseInt { input: String },
-
-    #[error("Invalid format. Expected `--- scanner x ---', found `{found}'.")]
-    Header { found: String },
-
-    #[error("Failed to parse line into beacon coordinates. Expected three integers separted by commas, but found `{line}'.")]
-    ParseBeacon { line: String },
-
-    #[error("Empty scanner region detected in input.")]
-    EmptyScanner,
+    #[error(transparent)]
+    Parse(#[from] parsing::ParseError),
 
     #[error("No solution. Expected `{parent}' and `{child}' to be connected, but could not find transformation.")]
     NoSolution { parent: usize, child: usize },
 
     #[error("Empty input. No scanners given.")]
     EmptyInput,
+
+    #[error(transparent)]
+    Common(#[from] common::AdventError),
 }
 
 // Iterate through all the 2-combinations of an iterator as tuples
@@ -76,60 +73,55 @@ impl Point {
         Self { x, y, z }
     }
 
-    fn parse(line: &str) -> Result<Point, AdventError> {
-        // Parse a line from the input into a 3D coordinate
-        line.split(',')
-            .map(|x| {
-                x.parse::<i32>().map_err(|_| AdventError::ParseInt {
-                    input: x.to_string(),
-                })
-            })
-            .collect::<Result<Vec<_>, AdventError>>()?
-            .into_iter()
-            .collect_tuple()
-            .ok_or(AdventError::ParseBeacon {
-                line: line.to_string(),
-            })
-            .map(Self::from_tuple)
-    }
-
     fn manhattan_distance(&self, other: &Self) -> i32 {
         // Get the manhattan distance between two points
         let diff = self - other;
         diff.x.abs() + diff.y.abs() + diff.z.abs()
     }
-}
-
-// Axis of rotation for transformations
-enum Axis {
-    X,
-    Y,
-    Z,
-}
-
-impl Point {
-    fn rotate(self, times: u8, axis: Axis) -> Self {
-        // Rotate a point CCW around an axis
-        if times == 0 {
-            return self;
-        }
-
-        Self::from_tuple(match axis {
-            Axis::X => (self.x, -self.z, self.y),
-            Axis::Y => (-self.z, self.y, self.x),
-            Axis::Z => (-self.y, self.x, self.z),
-        })
-        .rotate(times - 1, axis)
-    }
 
-    fn rotate_3d(self, x: u8, y: u8, z: u8) -> Self {
-        // Rotate a point in 3D
-        self.rotate(x, Axis::X)
-            .rotate(y, Axis::Y)
-            .rotate(z, Axis::Z)
+    fn squared_distance(&self, other: &Self) -> i32 {
+        // Get the squared Euclidean distance between two points. Like the
+        // Manhattan distance, this is rotation/reflection invariant, but it
+        // has far fewer accidental collisions between unrelated pairs, so
+        // it makes a better fingerprint for matching scanners.
+        let diff = self - other;
+        diff.x * diff.x + diff.y * diff.y + diff.z * diff.z
     }
 }
 
+// All 24 distinct orientations a cube can be in: the axis permutations with
+// an even number of sign flips relative to a right-handed frame (i.e. every
+// proper rotation, determinant +1). Replaces composing `(x_rot, y_rot,
+// z_rot)` in `0..4` each - 64 triples, about 40% of which just re-derive an
+// orientation another triple already produced - with exactly the 24 that
+// are actually distinct.
+const ORIENTATIONS: [fn(Point) -> Point; 24] = [
+    |p| Point { x: p.x, y: p.y, z: p.z },
+    |p| Point { x: p.x, y: -p.y, z: -p.z },
+    |p| Point { x: -p.x, y: p.y, z: -p.z },
+    |p| Point { x: -p.x, y: -p.y, z: p.z },
+    |p| Point { x: p.x, y: p.z, z: -p.y },
+    |p| Point { x: p.x, y: -p.z, z: p.y },
+    |p| Point { x: -p.x, y: p.z, z: p.y },
+    |p| Point { x: -p.x, y: -p.z, z: -p.y },
+    |p| Point { x: p.y, y: p.x, z: -p.z },
+    |p| Point { x: p.y, y: -p.x, z: p.z },
+    |p| Point { x: -p.y, y: p.x, z: p.z },
+    |p| Point { x: -p.y, y: -p.x, z: -p.z },
+    |p| Point { x: p.y, y: p.z, z: p.x },
+    |p| Point { x: p.y, y: -p.z, z: -p.x },
+    |p| Point { x: -p.y, y: p.z, z: -p.x },
+    |p| Point { x: -p.y, y: -p.z, z: p.x },
+    |p| Point { x: p.z, y: p.x, z: p.y },
+    |p| Point { x: p.z, y: -p.x, z: -p.y },
+    |p| Point { x: -p.z, y: p.x, z: -p.y },
+    |p| Point { x: -p.z, y: -p.x, z: p.y },
+    |p| Point { x: p.z, y: p.y, z: -p.x },
+    |p| Point { x: p.z, y: -p.y, z: p.x },
+    |p| Point { x: -p.z, y: p.y, z: p.x },
+    |p| Point { x: -p.z, y: -p.y, z: -p.x },
+];
+
 impl Add<&Point> for &Point {
     type Output = Point;
     fn add(self, other: &Point) -> Point {
@@ -152,51 +144,27 @@ impl Sub<&Point> for &Point {
     }
 }
 
-fn parse_scanner(input: &str) -> Result<HashSet<Point>, AdventError> {
-    // Parse a chunk of input into a scanner (represented as the set of its beacons)
-
-    lazy_static! {
-        static ref RE: Regex = Regex::new(r"--- scanner \d+ ---").unwrap();
-    }
-    let input = input.split('\n').collect::<Vec<_>>();
-
-    let (header, rest) = input.split_first().ok_or(AdventError::EmptyScanner)?;
-
-    // Check that the header is a match
-    // We don't actually use the number given in the input and index the scanners by the order they
-    // are parsed
-    if !RE.is_match(header) {
-        return Err(AdventError::Header {
-            found: header.to_string(),
-        });
-    }
-
-    rest.iter()
-        .filter(|line| !line.is_empty())
-        .map(|line| Point::parse(line))
-        .collect()
-}
-
 fn calc_pairwise_dists(beacons: &HashSet<Point>) -> HashMap<i32, [Point; 2]> {
     // Calculate the distances between every pair of beacons for every scanner
     HashMap::from_iter(
-        two_combinations!(beacons.iter()).map(|(a, b)| (a.manhattan_distance(b), [*a, *b])),
+        two_combinations!(beacons.iter()).map(|(a, b)| (a.squared_distance(b), [*a, *b])),
     )
 }
 
 fn find_intersecting_scanners(
     pairwise_dists: &[HashMap<i32, [Point; 2]>],
 ) -> HashMap<usize, Vec<usize>> {
-    // Build a graph of intersecting scanners by the intersection of the pairwise distances of
-    // their beacons
+    // Build a graph of *candidate* intersecting scanners by the intersection of the pairwise
+    // distances of their beacons. This is only a fingerprint filter, not proof: the caller still
+    // has to confirm each edge with `find_transformation` before trusting it.
     let mut adj_list = HashMap::<usize, Vec<usize>>::new();
     for ((i, a), (j, b)) in two_combinations!(pairwise_dists.iter().enumerate()) {
         let intersection = &key_set!(a) & &key_set!(b);
 
         // If two scanners have 66 (12 choose 2 (12 from problem statement and 2 because PAIRwise
-        // distance)) distances in common, then we can assume that they are connected
+        // distance)) distances in common, then they're likely connected
         // The pairwise distances are a kind of key for a scanner that is rotation-agnostic,
-        // allowing us to match scanners without brute-forcing the rotations
+        // allowing us to narrow down candidates without brute-forcing the rotations
         if (intersection).len() >= 66 {
             adj_list.entry(i).or_insert_with(Vec::new).push(j);
             adj_list.entry(j).or_insert_with(Vec::new).push(i);
@@ -205,10 +173,34 @@ fn find_intersecting_scanners(
     adj_list
 }
 
+fn confirm_intersecting_scanners(
+    candidates: &HashMap<usize, Vec<usize>>,
+    scanners: &[HashSet<Point>],
+    pairwise_dists: &[HashMap<i32, [Point; 2]>],
+) -> HashMap<usize, Vec<usize>> {
+    // Promote the fingerprint candidates to confirmed edges by actually finding a transformation
+    // with a real >= 12 beacon overlap, discarding any candidate that doesn't pan out
+    let mut confirmed = HashMap::<usize, Vec<usize>>::new();
+    for (&i, neighbours) in candidates {
+        for &j in neighbours {
+            if i >= j {
+                // Each undirected edge appears twice in `candidates` (once as (i, j), once as
+                // (j, i)); only need to confirm it once
+                continue;
+            }
+            if find_transformation(i, j, scanners, pairwise_dists).is_some() {
+                confirmed.entry(i).or_insert_with(Vec::new).push(j);
+                confirmed.entry(j).or_insert_with(Vec::new).push(i);
+            }
+        }
+    }
+    confirmed
+}
+
 fn build_tree(source: usize, adj_list: &HashMap<usize, Vec<usize>>) -> HashMap<usize, Vec<usize>> {
-    // Build a tree of the order in which we should visit each scanner
-    // The parent of a scanner should intersect the scanner, and each scanner should only be
-    // visited once
+    // Build a tree of the order in which we should visit each scanner, within `source`'s
+    // connected component. The parent of a scanner should intersect the scanner, and each
+    // scanner should only be visited once
     // This is basically breadth-first search
 
     let mut seen = HashSet::from([source]);
@@ -218,7 +210,9 @@ fn build_tree(source: usize, adj_list: &HashMap<usize, Vec<usize>>) -> HashMap<u
     while let Some(v) = q.pop() {
         let entry = children.entry(v).or_insert_with(Vec::new);
 
-        for w in &adj_list[&v] {
+        // `adj_list` has no entry at all for a scanner with no confirmed edges, rather than an
+        // empty `Vec`, so a scanner that's its own whole component still gets a one-node tree
+        for w in adj_list.get(&v).into_iter().flatten() {
             if seen.contains(w) {
                 continue;
             }
@@ -228,23 +222,21 @@ fn build_tree(source: usize, adj_list: &HashMap<usize, Vec<usize>>) -> HashMap<u
         }
     }
 
-    // Ensure graph is connected
-    assert_eq!(seen.len(), adj_list.len());
     children
 }
 
-fn transform(
-    beacons: &HashSet<Point>,
-    x_rotation: u8,
-    y_rotation: u8,
-    z_rotation: u8,
-    translate: Point,
-) -> HashSet<Point> {
-    HashSet::<Point>::from_iter(
-        beacons
-            .iter()
-            .map(|beacon| &beacon.rotate_3d(x_rotation, y_rotation, z_rotation) + &translate),
-    )
+/// Partition scanners into their connected components, so that scanners
+/// which never overlap any other scanner (or any of the scanners that
+/// overlap them) are still included, as their own single-scanner cluster,
+/// rather than making the whole solve fail.
+fn partition_into_clusters(count: usize, adj_list: &HashMap<usize, Vec<usize>>) -> Vec<HashSet<usize>> {
+    connected_components(&(0..count).collect::<Vec<_>>(), |v: &usize| {
+        adj_list.get(v).cloned().unwrap_or_default()
+    })
+}
+
+fn transform(beacons: &HashSet<Point>, orientation: fn(Point) -> Point, translate: Point) -> HashSet<Point> {
+    HashSet::<Point>::from_iter(beacons.iter().map(|beacon| &orientation(*beacon) + &translate))
 }
 
 fn find_transformation(
@@ -252,7 +244,7 @@ fn find_transformation(
     child: usize,
     scanners: &[HashSet<Point>],
     pairwise_dists: &[HashMap<i32, [Point; 2]>],
-) -> Option<(u8, u8, u8, Point)> {
+) -> Option<(fn(Point) -> Point, Point)> {
     // Find a matching point and transformation between parent scanner and child scanner
 
     let parent_dists = &pairwise_dists[parent];
@@ -270,20 +262,20 @@ fn find_transformation(
             parent_dists.get(dist).unwrap(),
             child_dists.get(dist).unwrap()
         ) {
-            // Check all 4*4*4 possibilities for rotations
+            // Check all 24 distinct cube orientations
             // Better than brute-forcing every possible pair of points between two scanners as
             // well, but still many possibilities
             // The whole algorithm is still pretty quick though
-            for (x_rot, y_rot, z_rot) in iproduct!(0..4, 0..4, 0..4) {
-                // Get the translation resulting in this match and rotation
-                let translation = parent_beacon - &child_beacon.rotate_3d(x_rot, y_rot, z_rot);
+            for orientation in ORIENTATIONS {
+                // Get the translation resulting in this match and orientation
+                let translation = parent_beacon - &orientation(*child_beacon);
 
-                // Transform all the child's beacons by this rotation and translation
-                let transformed = transform(&scanners[child], x_rot, y_rot, z_rot, translation);
+                // Transform all the child's beacons by this orientation and translation
+                let transformed = transform(&scanners[child], orientation, translation);
 
                 // If it's a match, return the transformation
                 if (&scanners[parent] & &transformed).len() >= 12 {
-                    return Some((x_rot, y_rot, z_rot, translation));
+                    return Some((orientation, translation));
                 }
             }
         }
@@ -319,11 +311,11 @@ fn build_collective(
                 .ok_or(AdventError::NoSolution { parent, child })
                 // If we find a transformation, apply it to the child result to transform it to our
                 // reference
-                .map(|(x_rot, y_rot, z_rot, translation)| {
+                .map(|(orientation, translation)| {
                     // Safe to unwrap; we're looping through a fixed-sized array
                     child_res
                         .into_iter()
-                        .map(|x| transform(&x, x_rot, y_rot, z_rot, translation))
+                        .map(|x| transform(&x, orientation, translation))
                         .collect_tuple()
                         .unwrap()
                 })
@@ -344,32 +336,53 @@ fn solve(input: String, question_part: QuestionPart) -> Result<usize, AdventErro
     // Solve everything from parsing down to the different desired results for the different parts
 
     // Parse input
-    let scanners = input
-        .split("\n\n")
-        .map(parse_scanner)
-        .collect::<Result<Vec<_>, AdventError>>()?;
+    // We don't actually use the scanner number given in the input and index the scanners by the
+    // order they are parsed
+    let scanners: Vec<HashSet<Point>> = parsing::scanners(&input)?
+        .into_iter()
+        .map(|block| block.points.into_iter().map(Point::from_tuple).collect())
+        .collect();
 
     // Calculate the distances between every pair of beacons for every scanner
     let pairwise_dists = scanners.iter().map(calc_pairwise_dists).collect::<Vec<_>>();
 
-    // Build a graph of intersecting scanners
-    let adj_list = find_intersecting_scanners(&pairwise_dists);
+    // Build a graph of candidate intersecting scanners from fingerprints, then confirm each
+    // candidate edge by actually finding a transformation, discarding any that don't pan out
+    let candidates = find_intersecting_scanners(&pairwise_dists);
+    let adj_list = confirm_intersecting_scanners(&candidates, &scanners, &pairwise_dists);
 
-    // Find an order in which to merge the scanners (make sure that we are not trying to merge
-    // non-intersecting scanners)
-    let children = build_tree(0, &adj_list);
+    // Partition into connected components: usually just one, but a fragmented input (some
+    // scanners never overlapping any other) gives one region per cluster instead of failing
+    let clusters = partition_into_clusters(scanners.len(), &adj_list);
 
-    // Merge everything
-    let [collective, scanners] = build_collective(0, &scanners, &pairwise_dists, &children)?;
+    // For each cluster, find an order in which to merge its scanners (make sure that we are not
+    // trying to merge non-intersecting scanners), then merge them
+    let results = clusters
+        .into_iter()
+        .map(|cluster| {
+            // Safe to unwrap; `connected_components` never returns an empty cluster
+            let source = *cluster.iter().min().unwrap();
+            let children = build_tree(source, &adj_list);
+            build_collective(source, &scanners, &pairwise_dists, &children)
+        })
+        .collect::<Result<Vec<[HashSet<Point>; 2]>, AdventError>>()?;
 
     Ok(match question_part {
         // In part one, we only want the number of unique beacons
-        // Once we transform all the beacons relative to scanner zero, we just put them in a set to
-        // get the unique count
-        QuestionPart::One => collective.len(),
-        // In part two, we want the maximum manhattan distance between all the scanner origins
-        QuestionPart::Two => two_combinations!(scanners.iter())
-            .map(|(a, b)| a.manhattan_distance(b) as usize)
+        // Once we transform all the beacons relative to their cluster's root, we just put them
+        // in a set to get the unique count, then sum over clusters (their beacon sets are
+        // disjoint, since two scanners in different clusters never shared any beacons)
+        QuestionPart::One => results.iter().map(|[collective, _]| collective.len()).sum(),
+        // In part two, we want the maximum manhattan distance between all the scanner origins.
+        // Distances are only meaningful within a cluster (different clusters have no shared
+        // reference frame), so take the largest within any one cluster.
+        QuestionPart::Two => results
+            .iter()
+            .filter_map(|[_, origins]| {
+                two_combinations!(origins.iter())
+                    .map(|(a, b)| a.manhattan_distance(b) as usize)
+                    .max()
+            })
             .max()
             .ok_or(AdventError::EmptyInput)?,
     })
@@ -387,10 +400,19 @@ fn day_19() -> Result<usize, AdventError> {
         }),
     }?;
 
-    let mut input = String::new();
-    stdin().lock().read_to_string(&mut input)?;
+    // Default to stdin so the binary still works as a plain filter; pass
+    // `--fetch` to pull the real puzzle input (or `--fetch --example` for the
+    // worked example) via `common::input` instead.
+    let puzzle_input = if args.iter().any(|arg| arg == "--fetch") {
+        let example = args.iter().any(|arg| arg == "--example");
+        input::fetch(YEAR, DAY, example)?
+    } else {
+        let mut puzzle_input = String::new();
+        stdin().lock().read_to_string(&mut puzzle_input)?;
+        puzzle_input
+    };
 
-    solve(input, question_part)
+    solve(puzzle_input, question_part)
 }
 
 fn main() {
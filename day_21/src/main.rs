@@ -1,9 +1,10 @@
-use lazy_static::lazy_static;
-use regex::Regex;
+use common::parser;
+use common::parsing;
+use nom::bytes::complete::tag;
+use nom::sequence::preceded;
 use std::cmp::max;
 use std::env;
 use std::io::{stdin, Read};
-use std::num::ParseIntError;
 use std::process::exit;
 use thiserror::Error;
 
@@ -22,27 +23,24 @@ pub enum AdventError {
     Io(#[from] std::io::Error),
 
     #[error(transparent)]
-    ParseInt(#[from] ParseIntError),
+    Parse(#[from] parsing::ParseError),
 
     #[error("Please specify `part-one' or `part-two' as the first argument.")]
     NoPartArgument,
-
-    #[error("Invalid input format detected.")]
-    FormatError,
 }
 
 fn parse() -> Result<(u8, u8), AdventError> {
-    lazy_static! {
-        static ref RE: Regex =
-            Regex::new(r"Player 1 starting position: (\d+)\nPlayer 2 starting position: (\d+)")
-                .unwrap();
-    }
-
     let mut input = String::new();
     stdin().lock().read_to_string(&mut input)?;
 
-    let caps = RE.captures(&input).ok_or(AdventError::FormatError)?;
-    Ok((caps[1].parse()?, caps[2].parse()?))
+    let (p0, p1) = parsing::run(
+        preceded(
+            tag("Player 1 starting position: "),
+            parser!(u64 "\nPlayer 2 starting position: " u64),
+        ),
+        input.trim_end(),
+    )?;
+    Ok((p0 as u8, p1 as u8))
 }
 
 fn part_one(i: u32, pos: (u8, u8), score: (u32, u32), target_score: u32) -> u32 {
@@ -69,28 +67,51 @@ fn part_one(i: u32, pos: (u8, u8), score: (u32, u32), target_score: u32) -> u32
 
 // Possible quantum rolls and their counts
 const POSIBILITIES: [(u8, u8); 7] = [(3, 1), (4, 3), (5, 6), (6, 7), (7, 6), (8, 3), (9, 1)];
-fn part_two(pos: (u8, u8), score: (u8, u8), mul: u64, target_score: u8) -> (u64, u64) {
-    // Naive solution, but only takes a few seconds
 
+// Dense cache over every reachable `(pos0, pos1, score0, score1)` state: positions are 1..=10
+// (10 values) and scores are 0..=20 (21 values, since reaching the target is the base case and
+// never gets indexed), so `21 * 10 * 21 * 10` entries cover the whole state space.
+type Cache = Vec<Option<(u64, u64)>>;
+
+fn new_cache() -> Cache {
+    vec![None; 21 * 10 * 21 * 10]
+}
+
+fn cache_index(pos: (u8, u8), score: (u8, u8)) -> usize {
+    let pos0 = (pos.0 - 1) as usize;
+    let pos1 = (pos.1 - 1) as usize;
+    ((score.0 as usize * 10 + pos0) * 21 + score.1 as usize) * 10 + pos1
+}
+
+fn part_two(cache: &mut Cache, pos: (u8, u8), score: (u8, u8), target_score: u8) -> (u64, u64) {
     // The current player is always in index zero. Flip it around each time so we don't have to
     // deal with tracking whose turn it is
 
-    // If somebody won, return
+    // If the other player already reached the target on the previous ply, the current player lost
     if score.1 >= target_score {
-        return (0, mul);
+        return (0, 1);
+    }
+
+    let index = cache_index(pos, score);
+    if let Some(wins) = cache[index] {
+        return wins;
     }
-    POSIBILITIES
-        .iter()
-        .map(|(posibility, count)| {
-            let new_pos = (pos.0 + posibility - 1) % 10 + 1;
-            part_two(
-                (pos.1, new_pos),
-                (score.1, score.0 + new_pos),
-                mul * *count as u64,
-                target_score,
-            )
-        })
-        .fold((0, 0), |a, b| (a.0 + b.1, a.1 + b.0))
+
+    let mut wins = (0u64, 0u64);
+    for (roll, count) in POSIBILITIES {
+        let new_pos = (pos.0 + roll - 1) % 10 + 1;
+        let (w_other, w_current) = part_two(
+            cache,
+            (pos.1, new_pos),
+            (score.1, score.0 + new_pos),
+            target_score,
+        );
+        wins.0 += count as u64 * w_current;
+        wins.1 += count as u64 * w_other;
+    }
+
+    cache[index] = Some(wins);
+    wins
 }
 
 fn day_21() -> Result<u64, AdventError> {
@@ -116,7 +137,8 @@ fn day_21() -> Result<u64, AdventError> {
         QuestionPart::Two => {
             let target_score = 21;
             let starting_score = (0, 0);
-            let wins = part_two(starting_position, starting_score, 1, target_score);
+            let mut cache = new_cache();
+            let wins = part_two(&mut cache, starting_position, starting_score, target_score);
 
             // In part two, get the maximum number of wins between the two players
             max(wins.0, wins.1)
@@ -145,8 +167,9 @@ mod test {
 
     #[test]
     fn test_part_two() {
+        let mut cache = new_cache();
         assert_eq!(
-            part_two((4, 8), (0, 0), 1, 21),
+            part_two(&mut cache, (4, 8), (0, 0), 21),
             (444356092776315, 341960390180808)
         );
     }
@@ -1,6 +1,7 @@
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::cmp::{max, min};
+use std::collections::HashMap;
 use std::env;
 use std::io::{stdin, BufRead};
 use std::ops::Sub;
@@ -66,7 +67,7 @@ impl Axis {
     }
 }
 
-// Rectangle, used for projections of cuboids
+// A 2D rectangle, for puzzles that only need two axes
 #[derive(Debug, Clone, PartialEq)]
 struct Rect {
     x: Axis,
@@ -74,6 +75,10 @@ struct Rect {
 }
 
 impl Rect {
+    fn new(x: Axis, y: Axis) -> Self {
+        Self { x, y }
+    }
+
     // Check if two rectangles intersect
     // If bottom left above or right of other's top right
     // or top right below or left of other's bottom left
@@ -84,93 +89,198 @@ impl Rect {
             && (self.y.start <= other.y.end)
             && (self.y.end >= other.y.start)
     }
-}
 
-#[derive(Debug, Clone, PartialEq, Copy)]
-struct Cuboid {
-    x: Axis,
-    y: Axis,
-    z: Axis,
-}
+    fn has_area(&self) -> bool {
+        !self.x.is_empty() && !self.y.is_empty()
+    }
 
-impl Cuboid {
-    fn new(x: Axis, y: Axis, z: Axis) -> Self {
-        Self { x, y, z }
+    fn area(&self) -> i64 {
+        self.x.extent() * self.y.extent()
     }
 
-    // Projections for each axis into rectangle
-    // The map from y, z to x, y is sometimes arbitrary, but as long as it's consistent, it should
-    // be logically correct
-    fn project_x(&self) -> Rect {
-        Rect {
-            x: self.y,
-            y: self.z,
-        }
+    fn limit(&self, start: i64, end: i64) -> Option<Self> {
+        Some(Self {
+            x: self.x.limit(start, end)?,
+            y: self.y.limit(start, end)?,
+        })
     }
 
-    fn project_y(&self) -> Rect {
-        Rect {
-            x: self.x,
-            y: self.z,
-        }
+    fn where_x(&self, x: Axis) -> Self {
+        Self { x, y: self.y }
     }
 
-    fn project_z(&self) -> Rect {
-        Rect {
-            x: self.x,
-            y: self.y,
+    fn where_y(&self, y: Axis) -> Self {
+        Self { x: self.x, y }
+    }
+}
+
+impl<'a> Sub<&'a Rect> for &'a Rect {
+    type Output = Vec<Rect>;
+    fn sub(self, other: Self) -> Self::Output {
+        // Same slab-cutting idea as `HyperRect`'s subtraction, just for two axes instead of three
+        if !self.intersects(other) {
+            return vec![self.clone()];
         }
+
+        let shrink_x = self.where_x(Axis::new(
+            max(self.x.start, other.x.start),
+            min(other.x.end, self.x.end),
+        ));
+        let left = self.where_x(Axis::new(self.x.start, shrink_x.x.start - 1));
+        let right = self.where_x(Axis::new(shrink_x.x.end + 1, self.x.end));
+
+        let shrink_y = shrink_x.where_y(Axis::new(
+            max(self.y.start, other.y.start),
+            min(other.y.end, self.y.end),
+        ));
+        let back = shrink_x.where_y(Axis::new(shrink_x.y.start, shrink_y.y.start - 1));
+        let front = shrink_x.where_y(Axis::new(shrink_y.y.end + 1, shrink_x.y.end));
+
+        [left, right, back, front]
+            .into_iter()
+            .filter(|r| r.has_area())
+            .collect()
     }
+}
 
-    fn intersects(&self, other: &Self) -> bool {
-        // Two cuboids intersect if and only if all three of their projections intersect
-        self.project_x().intersects(&other.project_x())
-            && self.project_y().intersects(&other.project_y())
-            && self.project_z().intersects(&other.project_z())
+/// The total area covered by the union of `rects`, however much they overlap.
+pub fn covered_area(rects: &[Rect]) -> i64 {
+    let mut state: Vec<Rect> = Vec::new();
+    for rect in rects {
+        state = state.iter().map(|r| r - rect).flatten().collect();
+        state.push(rect.clone());
     }
+    state.iter().map(|r| r.area()).sum()
+}
 
-    // Get a clone of the cuboid where the given axis is changed
-    fn where_x(&self, x: Axis) -> Self {
-        Self {
-            x,
-            y: self.y,
-            z: self.z,
-        }
+/// The width covered at a single row `y`, i.e. the length of the union of every rect's x-extent
+/// that includes that row.
+pub fn row_coverage(rects: &[Rect], y: i64) -> i64 {
+    let sliced: Vec<Rect> = rects
+        .iter()
+        .filter(|r| r.y.start <= y && y <= r.y.end)
+        .map(|r| r.where_y(Axis::new(y, y)))
+        .collect();
+    covered_area(&sliced)
+}
+
+// An axis-aligned box in an arbitrary number of dimensions, one `Axis` per dimension. `Cuboid`
+// (below) is just the 3-dimensional case; keeping the dimension count generic means a later puzzle
+// with e.g. a 4th "w" axis can reuse the exact same subtraction/volume/intersection logic.
+#[derive(Debug, Clone, PartialEq)]
+struct HyperRect {
+    axes: Vec<Axis>,
+}
+
+impl HyperRect {
+    fn new(axes: Vec<Axis>) -> Self {
+        Self { axes }
     }
 
-    fn where_y(&self, y: Axis) -> Self {
-        Self {
-            x: self.x,
-            y,
-            z: self.z,
-        }
+    // Get a clone of the box where the axis at `dim` is changed
+    fn where_axis(&self, dim: usize, axis: Axis) -> Self {
+        let mut axes = self.axes.clone();
+        axes[dim] = axis;
+        Self::new(axes)
     }
-    fn where_z(&self, z: Axis) -> Self {
-        Self {
-            x: self.x,
-            y: self.y,
-            z,
-        }
+
+    fn intersects(&self, other: &Self) -> bool {
+        // Two boxes intersect if and only if they overlap on every axis
+        self.axes
+            .iter()
+            .zip(&other.axes)
+            .all(|(a, b)| a.start <= b.end && a.end >= b.start)
     }
 
     fn limit(&self, start: i64, end: i64) -> Option<Self> {
-        // Restrict a cuboid down to start..end in all dimensions
+        // Restrict a box down to start..end in all dimensions
         // or None if any axis is outside of that range
-        Some(Self {
-            x: self.x.limit(start, end)?,
-            y: self.y.limit(start, end)?,
-            z: self.z.limit(start, end)?,
-        })
+        let axes = self
+            .axes
+            .iter()
+            .map(|axis| axis.limit(start, end))
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self::new(axes))
+    }
+
+    // The overlap of two boxes, or `None` if they don't intersect
+    fn intersect(&self, other: &Self) -> Option<Self> {
+        let axes = self
+            .axes
+            .iter()
+            .zip(&other.axes)
+            .map(|(a, b)| Axis::new(max(a.start, b.start), min(a.end, b.end)))
+            .collect();
+        let overlap = Self::new(axes);
+        overlap.has_volume().then_some(overlap)
     }
 
     fn has_volume(&self) -> bool {
-        // Whether a cuboid has (positive) volume
-        !self.x.is_empty() && !self.y.is_empty() && !self.z.is_empty()
+        // Whether a box has (positive) volume
+        self.axes.iter().all(|axis| !axis.is_empty())
     }
 
     fn volume(&self) -> i64 {
-        // Get a cuboid's volume (width * length * height)
-        self.x.extent() * self.y.extent() * self.z.extent()
+        // Get a box's volume (the product of its extent in every dimension)
+        self.axes.iter().map(Axis::extent).product()
+    }
+}
+
+impl<'a> Sub<&'a HyperRect> for &'a HyperRect {
+    type Output = Vec<HyperRect>;
+    fn sub(self, other: Self) -> Self::Output {
+        // This is kind of the secret of this implementation
+        // Return a set of boxes whose union is the difference between `self` minus `other`
+        // These returned boxes must not intersect
+
+        // Trivial case. If other doesn't intersect us, just return us
+        // This is actually required and not an optimization. The rest of the algorithm does not
+        // work if not intersecting
+        if !self.intersects(other) {
+            return vec![self.clone()];
+        }
+
+        // Walk the dimensions one at a time. For each one, split off the part of `shrink` (the
+        // search space remaining after the previous dimensions were handled) that's below and
+        // above `other`'s extent on this axis, then narrow `shrink` itself down to the remaining
+        // overlap before moving on to the next dimension. By the time every dimension has been
+        // visited, `shrink` is exactly `self` intersected with `other`, and everything split off
+        // along the way tiles the rest of `self` without overlapping `other` or each other.
+        let mut pieces = Vec::new();
+        let mut shrink = self.clone();
+        for dim in 0..self.axes.len() {
+            let overlap = Axis::new(
+                max(shrink.axes[dim].start, other.axes[dim].start),
+                min(other.axes[dim].end, shrink.axes[dim].end),
+            );
+            let low = shrink.where_axis(dim, Axis::new(shrink.axes[dim].start, overlap.start - 1));
+            let high = shrink.where_axis(dim, Axis::new(overlap.end + 1, shrink.axes[dim].end));
+            pieces.extend([low, high].into_iter().filter(|piece| piece.has_volume()));
+            shrink = shrink.where_axis(dim, overlap);
+        }
+        pieces
+    }
+}
+
+// The 3-dimensional case of `HyperRect`, which is all this puzzle actually needs.
+type Cuboid = HyperRect;
+
+impl Cuboid {
+    // Named `cuboid` rather than `new`: `Cuboid` is a type alias for `HyperRect`, not a distinct
+    // type, so this impl block adds inherent methods directly onto `HyperRect` and can't declare
+    // a second `new` alongside `HyperRect::new`.
+    fn cuboid(x: Axis, y: Axis, z: Axis) -> Self {
+        HyperRect::new(vec![x, y, z])
+    }
+
+    fn x(&self) -> Axis {
+        self.axes[0]
+    }
+    fn y(&self) -> Axis {
+        self.axes[1]
+    }
+    fn z(&self) -> Axis {
+        self.axes[2]
     }
 
     pub fn parse(line: &str) -> Result<Self, AdventError> {
@@ -188,7 +298,7 @@ impl Cuboid {
         let [x1, x2, y1, y2, z1, z2] = ["x1", "x2", "y1", "y2", "z1", "z2"]
             .map(|which| caps.name(which).unwrap().as_str().parse::<i64>().unwrap());
 
-        Ok(Cuboid::new(
+        Ok(Cuboid::cuboid(
             Axis::new(x1, x2),
             Axis::new(y1, y2),
             Axis::new(z1, z2),
@@ -196,54 +306,121 @@ impl Cuboid {
     }
 }
 
-impl<'a> Sub<&'a Cuboid> for &'a Cuboid {
-    type Output = Vec<Cuboid>;
-    fn sub(self, other: Self) -> Self::Output {
-        // This is kind of the secret of this implementation
-        // Return a set of cuboids whose union is the difference between `self` minus `other`
-        // These returned cuboids must not intersect
+// A linear inequality `sum(coeff * var) <= bound`, stored sparsely as `(var_index, coeff)` pairs
+// so constraints over many dimensions with only a few nonzero terms stay cheap to combine.
+#[derive(Debug, Clone)]
+struct Constraint {
+    terms: Vec<(usize, f64)>,
+    bound: f64,
+}
 
-        // Trivial case. If other doesn't intersect us, just return us
-        // This is actually required and not an optimization. The rest of the algorithm does not
-        // work if not intersecting
-        if !self.intersects(other) {
-            return vec![*self];
-        }
+impl Constraint {
+    fn new(terms: Vec<(usize, f64)>, bound: f64) -> Self {
+        Self { terms, bound }
+    }
 
-        // First, do the x axis and find `left` and `right`, which are the part of ourself to the
-        // left of other's left face and the part of ourself to the right of other's right face,
-        // respectively
-        // `shrink_x` becomes the new search space. The returned cuboids cannot intersect,
-        // so we now have to consider ourselves - left - right
-        let shrink_x = self.where_x(Axis::new(
-            max(self.x.start, other.x.start),
-            min(other.x.end, self.x.end),
-        ));
-        let left = self.where_x(Axis::new(self.x.start, shrink_x.x.start - 1));
-        let right = self.where_x(Axis::new(shrink_x.x.end + 1, self.x.end));
+    fn coeff(&self, var: usize) -> f64 {
+        self.terms
+            .iter()
+            .find(|(v, _)| *v == var)
+            .map_or(0.0, |(_, c)| *c)
+    }
 
-        // Same logic as x except now we are searching in the reduced space (-left-right)
-        let shrink_y = shrink_x.where_y(Axis::new(
-            max(self.y.start, other.y.start),
-            min(other.y.end, self.y.end),
-        ));
-        let back = shrink_x.where_y(Axis::new(shrink_x.y.start, shrink_y.y.start - 1));
-        let front = shrink_x.where_y(Axis::new(shrink_y.y.end + 1, shrink_x.y.end));
+    fn without(&self, var: usize) -> impl Iterator<Item = &(usize, f64)> {
+        self.terms.iter().filter(move |(v, _)| *v != var)
+    }
+}
 
-        // Same logic again but for z, searching in the space reduced in x and y
-        let shrink_z = shrink_y.where_z(Axis::new(
-            max(self.z.start, other.z.start),
-            min(other.z.end, self.z.end),
-        ));
-        let bottom = shrink_y.where_z(Axis::new(shrink_y.z.start, shrink_z.z.start - 1));
-        let top = shrink_y.where_z(Axis::new(shrink_z.z.end + 1, shrink_y.z.end));
+// A convex region described as the intersection of half-spaces (linear inequalities), rather than
+// as axes like `HyperRect`. This is what it takes to represent a rotated or sheared box; an
+// axis-aligned `Cuboid` is just the special case of two opposing box constraints per dimension
+// (`x <= end` and `-x <= -start`), see `Polytope::from_cuboid`.
+//
+// Only the feasibility side is implemented so far (`is_feasible`/`intersect`/`intersects`, via
+// Fourier-Motzkin elimination): enough to tell whether two regions overlap at all. There's no
+// `volume()` yet, so this can't be dropped into `num_on_cubes_signed` as a drop-in replacement
+// for `Cuboid` the way a real union-volume mode would need — computing the volume of a general
+// half-space-described polytope means either a simplex decomposition over its vertices or a
+// grid-free integration of the constraint system, neither of which is done here. That's left as
+// follow-up work; this lays the feasibility-checking groundwork for it.
+#[derive(Debug, Clone)]
+struct Polytope {
+    dims: usize,
+    constraints: Vec<Constraint>,
+}
 
-        // Filter out all the cuboids with negative volume (that means that `other` was sticking
-        // out of ourselves on that side)
-        [left, right, back, front, bottom, top]
-            .into_iter()
-            .filter(|c| c.has_volume())
-            .collect()
+impl Polytope {
+    fn new(dims: usize, constraints: Vec<Constraint>) -> Self {
+        Self { dims, constraints }
+    }
+
+    fn from_cuboid(cuboid: &Cuboid) -> Self {
+        let constraints = cuboid
+            .axes
+            .iter()
+            .enumerate()
+            .flat_map(|(dim, axis)| {
+                [
+                    Constraint::new(vec![(dim, 1.0)], axis.end as f64),
+                    Constraint::new(vec![(dim, -1.0)], -(axis.start as f64)),
+                ]
+            })
+            .collect();
+        Self::new(cuboid.axes.len(), constraints)
+    }
+
+    // Eliminate variable `v` via Fourier-Motzkin: every constraint with a positive coefficient on
+    // `v` gives an upper bound for it, every constraint with a negative one gives a lower bound,
+    // and combining each upper/lower pair (scaled so `v`'s coefficient cancels) yields a
+    // constraint over the remaining variables that's implied exactly when both bounds agree that
+    // some value of `v` satisfies them. Constraints that don't mention `v` pass through unchanged.
+    fn eliminate(&self, v: usize) -> Self {
+        let (upper, rest): (Vec<_>, Vec<_>) =
+            self.constraints.iter().cloned().partition(|c| c.coeff(v) > 0.0);
+        let (lower, mut constraints): (Vec<_>, Vec<_>) =
+            rest.into_iter().partition(|c| c.coeff(v) < 0.0);
+
+        for p in &upper {
+            for n in &lower {
+                let pc = p.coeff(v);
+                let nc = -n.coeff(v);
+
+                let mut terms: HashMap<usize, f64> = HashMap::new();
+                for &(var, coeff) in p.without(v) {
+                    *terms.entry(var).or_insert(0.0) += coeff * nc;
+                }
+                for &(var, coeff) in n.without(v) {
+                    *terms.entry(var).or_insert(0.0) += coeff * pc;
+                }
+                constraints.push(Constraint::new(
+                    terms.into_iter().collect(),
+                    p.bound * nc + n.bound * pc,
+                ));
+            }
+        }
+        Self::new(self.dims, constraints)
+    }
+
+    // Whether the system of inequalities has any solution: eliminate every variable in turn, and
+    // the system is feasible if and only if every constraint left over (now with no variables at
+    // all, just `0 <= bound`) holds.
+    fn is_feasible(&self) -> bool {
+        let system = (0..self.dims).fold(self.clone(), |system, v| system.eliminate(v));
+        system.constraints.iter().all(|c| c.bound >= -1e-9)
+    }
+
+    fn intersect(&self, other: &Self) -> Self {
+        let constraints = self
+            .constraints
+            .iter()
+            .chain(&other.constraints)
+            .cloned()
+            .collect();
+        Self::new(self.dims.max(other.dims), constraints)
+    }
+
+    fn intersects(&self, other: &Self) -> bool {
+        self.intersect(other).is_feasible()
     }
 }
 
@@ -262,31 +439,17 @@ fn parse(line: &str) -> Result<(bool, Cuboid), AdventError> {
     Ok((left == "on", Cuboid::parse(right)?))
 }
 
-fn num_on_cubes(lines: Vec<&str>, question_part: QuestionPart) -> Result<i64, AdventError> {
-    // Calculate the number of "on" cubes after performing all steps in the input
-
-    // Every time we see an "on" cuboid in the input, subtract it from all the others, then add the
-    // new one
-    // Every time we see an "off" cuboid in the input, subtract it from all the saved "on" cuboids
-    // This is equivalent, but allows us to calculate the volume of all these non-intersecting
-    // cuboids to get the number of 1x1x1 cubes that are "on" without a n^3 loop, which is much
-    // much more efficient
-
-    let cuboids = lines
-        .iter()
-        .map(|l| parse(l))
-        .collect::<Result<Vec<_>, AdventError>>()?;
-
-    let cuboids = match question_part {
-        // In part one, only consider the cuboids 50 spaces from the origin
-        QuestionPart::One => cuboids
-            .into_iter()
-            .filter_map(|(s, c)| Some((s, c.limit(-50, 50)?)))
-            .collect(),
-        // In part two, consider all cuboids
-        QuestionPart::Two => cuboids,
-    };
+// Which of the two equivalent reactor solvers to run. Both produce the same answer; `Subtraction`
+// keeps the disjoint set of "on" cuboids around explicitly, while `SignedCount` only tracks signed
+// weights and so never needs to union cuboids back together.
+#[derive(Debug, Clone, Copy)]
+enum Engine {
+    Subtraction,
+    SignedCount,
+}
 
+// The disjoint set of "on" cuboids left after folding every step in, via subtraction.
+fn subtraction_state(cuboids: Vec<(bool, Cuboid)>) -> Vec<Cuboid> {
     fn recurse(state: Vec<Cuboid>, cuboids: Vec<(bool, Cuboid)>) -> Vec<Cuboid> {
         match cuboids.split_first() {
             None => state,
@@ -303,7 +466,7 @@ fn num_on_cubes(lines: Vec<&str>, question_part: QuestionPart) -> Result<i64, Ad
                 let state = if *toggle_to {
                     state
                         .into_iter()
-                        .chain([*cuboid].into_iter())
+                        .chain([cuboid.clone()].into_iter())
                         .collect::<Vec<Cuboid>>()
                 } else {
                     state
@@ -312,11 +475,109 @@ fn num_on_cubes(lines: Vec<&str>, question_part: QuestionPart) -> Result<i64, Ad
             }
         }
     }
-    let final_state = recurse(vec![], cuboids);
+    recurse(vec![], cuboids)
+}
 
+fn num_on_cubes_subtraction(cuboids: Vec<(bool, Cuboid)>) -> i64 {
     // Get the volume of all the non-intersecting, "on" cuboids
     // Equivalent to the number of "on" cubes
-    Ok(final_state.iter().map(|c| c.volume()).sum::<i64>())
+    subtraction_state(cuboids)
+        .iter()
+        .map(|c| c.volume())
+        .sum::<i64>()
+}
+
+// Inclusion-exclusion by signed weight instead of by keeping a disjoint set of "on" cuboids: for
+// every cuboid `C` in the input, every previously-seen `(D, weight)` that overlaps it contributes
+// `-weight` over the overlap, canceling out the part of `D` that would otherwise be double
+// counted; if `C` itself is "on", it also contributes its own `+1`. Summing `weight * volume` over
+// the resulting list counts each 1x1x1 cube that ends up "on" exactly once, without ever having to
+// union cuboids back into a disjoint set.
+fn num_on_cubes_signed(cuboids: Vec<(bool, Cuboid)>) -> i64 {
+    let mut weighted: Vec<(Cuboid, i64)> = Vec::new();
+    for (toggle_to, cuboid) in cuboids {
+        let overlaps: Vec<(Cuboid, i64)> = weighted
+            .iter()
+            .filter_map(|(d, weight)| Some((d.intersect(&cuboid)?, -weight)))
+            .collect();
+        weighted.extend(overlaps);
+        if toggle_to {
+            weighted.push((cuboid, 1));
+        }
+    }
+    weighted.iter().map(|(c, weight)| c.volume() * weight).sum()
+}
+
+fn num_on_cubes(
+    lines: Vec<&str>,
+    question_part: QuestionPart,
+    engine: Engine,
+) -> Result<i64, AdventError> {
+    // Calculate the number of "on" cubes after performing all steps in the input
+
+    // Every time we see an "on" cuboid in the input, subtract it from all the others, then add the
+    // new one
+    // Every time we see an "off" cuboid in the input, subtract it from all the saved "on" cuboids
+    // This is equivalent, but allows us to calculate the volume of all these non-intersecting
+    // cuboids to get the number of 1x1x1 cubes that are "on" without a n^3 loop, which is much
+    // much more efficient
+
+    let cuboids = lines
+        .iter()
+        .map(|l| parse(l))
+        .collect::<Result<Vec<_>, AdventError>>()?;
+
+    let cuboids = match question_part {
+        // In part one, only consider the cuboids 50 spaces from the origin
+        QuestionPart::One => cuboids
+            .into_iter()
+            .filter_map(|(s, c)| Some((s, c.limit(-50, 50)?)))
+            .collect(),
+        // In part two, consider all cuboids
+        QuestionPart::Two => cuboids,
+    };
+
+    Ok(match engine {
+        Engine::Subtraction => num_on_cubes_subtraction(cuboids),
+        Engine::SignedCount => num_on_cubes_signed(cuboids),
+    })
+}
+
+// The solved reactor core: a disjoint set of "on" cuboids, cheap to query repeatedly without
+// re-running the whole fold over every step again.
+pub struct ReactorState {
+    cuboids: Vec<Cuboid>,
+}
+
+impl ReactorState {
+    pub fn contains(&self, x: i64, y: i64, z: i64) -> bool {
+        self.cuboids.iter().any(|c| {
+            (c.x().start..=c.x().end).contains(&x)
+                && (c.y().start..=c.y().end).contains(&y)
+                && (c.z().start..=c.z().end).contains(&z)
+        })
+    }
+
+    /// How many "on" cubes fall within `region`, clipping each stored cuboid down to it first.
+    pub fn count_on_within(&self, region: &Cuboid) -> i64 {
+        self.cuboids
+            .iter()
+            .filter_map(|c| c.intersect(region))
+            .map(|c| c.volume())
+            .sum()
+    }
+}
+
+/// Fold every step in the input into the final reactor state, for callers that want to issue
+/// repeated point/region queries instead of a single total volume.
+pub fn run(lines: Vec<&str>) -> Result<ReactorState, AdventError> {
+    let cuboids = lines
+        .iter()
+        .map(|l| parse(l))
+        .collect::<Result<Vec<_>, AdventError>>()?;
+    Ok(ReactorState {
+        cuboids: subtraction_state(cuboids),
+    })
 }
 
 fn day_22() -> Result<i64, AdventError> {
@@ -337,7 +598,7 @@ fn day_22() -> Result<i64, AdventError> {
         .collect::<Result<Vec<String>, std::io::Error>>()?;
     let lines = lines.iter().map(|line| &line[..]).collect();
 
-    num_on_cubes(lines, question_part)
+    num_on_cubes(lines, question_part, Engine::Subtraction)
 }
 
 fn main() {
@@ -448,6 +709,19 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_polytope_intersects_matches_cuboid() -> Result<(), AdventError> {
+        let a = Cuboid::parse("x=-10..10,y=-2..2,z=-1..1")?;
+        let b = Cuboid::parse("x=-1..1,y=-1..1,z=-10..10")?;
+        assert!(Polytope::from_cuboid(&a).intersects(&Polytope::from_cuboid(&b)));
+
+        let a = Cuboid::parse("x=0..1,y=0..1,z=0..1")?;
+        let b = Cuboid::parse("x=2..3,y=2..3,z=2..3")?;
+        assert!(!Polytope::from_cuboid(&a).intersects(&Polytope::from_cuboid(&b)));
+
+        Ok(())
+    }
+
     #[test]
     fn test_example_part_one() -> Result<(), AdventError> {
         let input = vec![
@@ -474,7 +748,14 @@ mod test {
             "on x=-54112..-39298,y=-85059..-49293,z=-27449..7877",
             "on x=967..23432,y=45373..81175,z=27513..53682",
         ];
-        assert_eq!(num_on_cubes(input, QuestionPart::One)?, 590784);
+        assert_eq!(
+            num_on_cubes(input.clone(), QuestionPart::One, Engine::Subtraction)?,
+            590784
+        );
+        assert_eq!(
+            num_on_cubes(input, QuestionPart::One, Engine::SignedCount)?,
+            590784
+        );
         Ok(())
     }
 
@@ -542,8 +823,22 @@ mod test {
             "on x=-53470..21291,y=-120233..-33476,z=-44150..38147",
             "off x=-93533..-4276,y=-16170..68771,z=-104985..-24507",
         ];
-        assert_eq!(num_on_cubes(input.clone(), QuestionPart::One)?, 474140);
-        assert_eq!(num_on_cubes(input, QuestionPart::Two)?, 2758514936282235);
+        assert_eq!(
+            num_on_cubes(input.clone(), QuestionPart::One, Engine::Subtraction)?,
+            474140
+        );
+        assert_eq!(
+            num_on_cubes(input.clone(), QuestionPart::One, Engine::SignedCount)?,
+            474140
+        );
+        assert_eq!(
+            num_on_cubes(input.clone(), QuestionPart::Two, Engine::Subtraction)?,
+            2758514936282235
+        );
+        assert_eq!(
+            num_on_cubes(input, QuestionPart::Two, Engine::SignedCount)?,
+            2758514936282235
+        );
         Ok(())
     }
 }
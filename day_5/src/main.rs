@@ -1,19 +1,15 @@
+use common::parser;
+use common::parsing;
 use itertools::Itertools;
-use ndarray::prelude::*;
-use ndarray::Array;
-use regex::Regex;
-use std::cmp::max;
+use std::collections::HashMap;
 use std::env;
 use std::io::{stdin, BufRead};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum AdventError {
-    #[error("Invalid input")]
-    InvalidInput,
-
     #[error(transparent)]
-    Regex(#[from] regex::Error),
+    Parse(#[from] parsing::ParseError),
 
     #[error("Invalid command `{command:?}'. Expected `part-one' or `part-two'.")]
     InvalidCommand { command: String },
@@ -30,69 +26,70 @@ enum QuestionPart {
     Two,
 }
 
-fn sorted<A, T>(mut array: A) -> A
-where
-    A: AsMut<[T]>,
-    T: Ord,
-{
-    let slice = array.as_mut();
-    slice.sort();
-
-    array
-}
-
 #[derive(Debug, Clone)]
 struct Line {
-    x1: usize,
-    y1: usize,
-    x2: usize,
-    y2: usize,
+    x1: i64,
+    y1: i64,
+    x2: i64,
+    y2: i64,
 }
 
 impl Line {
     fn parse(text: &str) -> Result<Line, AdventError> {
-        let re = Regex::new(r"(\d+),(\d+) -> (\d+),(\d+)")?;
-        let (x1, y1, x2, y2) = re
-            .captures(text)
-            .ok_or(AdventError::InvalidInput)?
-            .iter()
-            .filter_map(|x| x?.as_str().parse().ok())
-            .collect_tuple()
-            .ok_or(AdventError::InvalidInput)?;
+        let (x1, y1, x2, y2) = parsing::run(parser!(i64 "," i64 " -> " i64 "," i64), text)?;
         Ok(Line { x1, y1, x2, y2 })
     }
 
-    fn sort(&self) -> Line {
-        // Sort pair of points by x value
-        // This guarantee is useful later on when doing the diagonals
-
-        if self.x1 < self.x2 {
-            self.clone()
-        } else {
-            Line {
-                x1: self.x2,
-                x2: self.x1,
-                y1: self.y2,
-                y2: self.y1,
-            }
+    fn is_diagonal(&self) -> bool {
+        self.x1 != self.x2 && self.y1 != self.y2
+    }
+
+    // Walk the line one cell at a time using a normalized (-1, 0, or 1) step in each axis. Works
+    // for horizontal, vertical, and (since the puzzle only ever gives 45-degree diagonals) any
+    // diagonal line alike, without needing to special-case any of them.
+    fn points(&self) -> impl Iterator<Item = (i64, i64)> + '_ {
+        let step_x = (self.x2 - self.x1).signum();
+        let step_y = (self.y2 - self.y1).signum();
+        let len = (self.x2 - self.x1).abs().max((self.y2 - self.y1).abs());
+        (0..=len).map(move |i| (self.x1 + step_x * i, self.y1 + step_y * i))
+    }
+}
+
+// Count how many lines cover each cell. A `HashMap` keyed on the cell itself (rather than a dense
+// grid sized to the largest coordinate) means memory only scales with the total length of the
+// segments, not with how far out their endpoints happen to be.
+fn count_overlaps(lines: &[Line], question_part: &QuestionPart) -> HashMap<(i64, i64), u32> {
+    let mut counts = HashMap::new();
+    for line in lines {
+        if matches!(question_part, QuestionPart::One) && line.is_diagonal() {
+            // In part one, we don't consider the diagonals
+            continue;
+        }
+        for point in line.points() {
+            *counts.entry(point).or_insert(0) += 1;
         }
     }
+    counts
 }
 
-fn print(a: &Array<i32, Ix2>) {
-    // Pretty print the array like in the website
-
-    for row in a.rows() {
-        println!(
-            "{}",
-            row.iter()
-                .map(|x| if x == &0 {
-                    ".".to_string()
-                } else {
-                    x.to_string()
-                })
-                .join("")
-        );
+fn print(counts: &HashMap<(i64, i64), u32>) {
+    // Pretty print the grid like on the website, but only over the occupied bounding box, since
+    // the coordinates are no longer known to start near zero
+    let Some(min_x) = counts.keys().map(|&(x, _)| x).min() else {
+        return;
+    };
+    let max_x = counts.keys().map(|&(x, _)| x).max().unwrap();
+    let min_y = counts.keys().map(|&(_, y)| y).min().unwrap();
+    let max_y = counts.keys().map(|&(_, y)| y).max().unwrap();
+
+    for y in min_y..=max_y {
+        let row = (min_x..=max_x)
+            .map(|x| match counts.get(&(x, y)) {
+                Some(count) => count.to_string(),
+                None => ".".to_string(),
+            })
+            .join("");
+        println!("{row}");
     }
 }
 
@@ -110,68 +107,13 @@ fn main() -> Result<(), AdventError> {
     let lines = stdin()
         .lock()
         .lines()
-        .map(|line| match line {
-            Ok(line) => Line::parse(&line[..]),
-            Err(e) => Err(AdventError::Io(e)),
-        })
+        .map(|line| Line::parse(&line.map_err(AdventError::Io)?))
         .collect::<Result<Vec<Line>, AdventError>>()?;
-    let lines: Vec<Line> = lines.iter().map(|l| l.sort()).collect();
-
-    let x_max = lines
-        .iter()
-        .map(|line| max(line.x1, line.x2))
-        .max()
-        .unwrap()
-        + 1;
-    let y_max = lines
-        .iter()
-        .map(|line| max(line.y1, line.y2))
-        .max()
-        .unwrap()
-        + 1;
-    let mut a: Array<i32, Ix2> = Array::zeros((x_max, y_max));
-
-    print(&a);
-    println!();
-    let points = lines
-        .iter()
-        .map(|line| {
-            if line.x1 == line.x2 {
-                let [small, big] = sorted([line.y1, line.y2]);
-                (small..=big).map(|y| [line.x1, y]).collect()
-            } else if line.y1 == line.y2 {
-                let [small, big] = sorted([line.x1, line.x2]);
-                (small..=big).map(|x| [x, line.y1]).collect()
-            } else {
-                match question_part {
-                    // In part one, we don't consider the diagonals
-                    QuestionPart::One => Vec::new(),
-                    QuestionPart::Two => (0..=(line.x2 - line.x1) as i32)
-                        .map(|i| {
-                            let [x, y1, y2] = [line.x1, line.y1, line.y2].map(|x| x as i32);
-                            [x + i, (y1 + (if y2 > y1 { i } else { -i }))].map(|x| x as usize)
-                        })
-                        .collect(),
-                }
-            }
-        })
-        .flatten()
-        .collect::<Vec<_>>();
-
-    for [x, y] in points {
-        // Cannot find a better way besides mutating
-        // Wanted to use reduce, but I cannot find a way to return the ndarray with a single cell
-        // incremented
-        if let Some(cell) = a.get_mut((y, x)) {
-            *cell += 1;
-        }
-    }
-    print(&a);
 
-    println!(
-        "{:?}",
-        a.iter().map(|x| if x >= &2 { 1 } else { 0 }).sum::<i32>()
-    );
+    let counts = count_overlaps(&lines, &question_part);
+    print(&counts);
+
+    println!("{}", counts.values().filter(|&&count| count >= 2).count());
 
     Ok(())
 }
@@ -0,0 +1,231 @@
+use common::{input, registry, AdventError, QuestionPart, Timings};
+use std::env;
+use std::io::{stdin, Read};
+use std::process::exit;
+use thiserror::Error;
+
+const YEAR: u16 = 2021;
+
+#[derive(Error, Debug)]
+enum RunnerError {
+    #[error("Usage: runner run [<day>|-d <days>] <part-one|part-two> [--example] [--stdin]\n       runner all [-d <days>] [--example] [--stdin]\n       runner bench -d <days> [-n <repeats>] [--example]\n       runner download <day>|-d <days> [--example]")]
+    MissingArguments,
+
+    #[error("Invalid day `{day}'. Expected a number.")]
+    InvalidDay { day: String },
+
+    #[error("Invalid day range `{spec}'. Expected e.g. `1,8,15' or `1..=25'.")]
+    InvalidDayRange { spec: String },
+
+    #[error("Unknown subcommand `{subcommand}'. Expected `run', `all', `bench', or `download'.")]
+    UnknownSubcommand { subcommand: String },
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Advent(#[from] AdventError),
+}
+
+/// Parse a day selector like `1,8,15` or `1..=25` into the individual days.
+fn parse_days(spec: &str) -> Result<Vec<u8>, RunnerError> {
+    let invalid = || RunnerError::InvalidDayRange {
+        spec: spec.to_string(),
+    };
+
+    if let Some((start, end)) = spec.split_once("..=") {
+        let start: u8 = start.parse().map_err(|_| invalid())?;
+        let end: u8 = end.parse().map_err(|_| invalid())?;
+        return Ok((start..=end).collect());
+    }
+
+    spec.split(',')
+        .map(|day| day.parse().map_err(|_| invalid()))
+        .collect()
+}
+
+fn flag_value(args: &[String], flag: &str) -> Option<String> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|i| args.get(i + 1))
+        .cloned()
+}
+
+fn get_input(day: u8, example: bool, use_stdin: bool) -> Result<String, RunnerError> {
+    if use_stdin {
+        let mut input = String::new();
+        stdin().lock().read_to_string(&mut input)?;
+        Ok(input)
+    } else {
+        Ok(input::fetch(YEAR, day, example)?)
+    }
+}
+
+/// Days to run: `-d <days>` if given, else the single positional day, else
+/// (bare `run part-one`) every day currently registered.
+fn select_days(args: &[String]) -> Result<Vec<u8>, RunnerError> {
+    if let Some(spec) = flag_value(args, "-d") {
+        return parse_days(&spec);
+    }
+    match args
+        .iter()
+        .find(|arg| !arg.starts_with('-') && !matches!(&arg[..], "part-one" | "part-two"))
+    {
+        Some(day) => Ok(vec![day.parse().map_err(|_| RunnerError::InvalidDay {
+            day: day.to_string(),
+        })?]),
+        None => Ok(registry().days(YEAR)),
+    }
+}
+
+fn run(args: &[String]) -> Result<String, RunnerError> {
+    let command = args
+        .iter()
+        .find(|arg| matches!(&arg[..], "part-one" | "part-two"))
+        .ok_or(RunnerError::MissingArguments)?;
+    let question_part = match &command[..] {
+        "part-one" => QuestionPart::One,
+        "part-two" => QuestionPart::Two,
+        _ => unreachable!(),
+    };
+
+    let days = select_days(args)?;
+    let example = args.iter().any(|arg| arg == "--example");
+    let use_stdin = args.iter().any(|arg| arg == "--stdin");
+
+    let mut lines = Vec::new();
+    for day in days {
+        let input = get_input(day, example, use_stdin)?;
+        let start = std::time::Instant::now();
+        let answer = registry().solve(YEAR, day, &input, question_part)?;
+        let elapsed = start.elapsed();
+        lines.push(format!("day {day:>2}: {answer} ({})", format_duration(elapsed)));
+    }
+
+    Ok(lines.join("\n"))
+}
+
+/// Solve both parts of every selected day and print a table of day, both answers, and elapsed
+/// time, with a total at the bottom. Unlike `bench`, this reports the actual answers (useful for
+/// a quick end-to-end sanity check across the whole year) rather than repeated timings.
+fn all(args: &[String]) -> Result<String, RunnerError> {
+    let days = match flag_value(args, "-d") {
+        Some(spec) => parse_days(&spec)?,
+        None => registry().days(YEAR),
+    };
+    let example = args.iter().any(|arg| arg == "--example");
+    let use_stdin = args.iter().any(|arg| arg == "--stdin");
+
+    let mut rows = Vec::new();
+    for day in days {
+        let input = get_input(day, example, use_stdin)?;
+        let start = std::time::Instant::now();
+        let part_one = registry().solve(YEAR, day, &input, QuestionPart::One)?;
+        let part_two = registry().solve(YEAR, day, &input, QuestionPart::Two)?;
+        let elapsed = start.elapsed();
+        rows.push((day, part_one, part_two, elapsed));
+    }
+
+    let mut table = format!(
+        "{:>3} | {:>20} | {:>20} | {:>10}\n",
+        "day", "part one", "part two", "elapsed"
+    );
+    for (day, part_one, part_two, elapsed) in &rows {
+        table += &format!(
+            "{:>3} | {:>20} | {:>20} | {:>10}\n",
+            day,
+            part_one,
+            part_two,
+            format_duration(*elapsed)
+        );
+    }
+    let total: std::time::Duration = rows.iter().map(|(_, _, _, elapsed)| *elapsed).sum();
+    table += &format!("total elapsed: {}", format_duration(total));
+
+    Ok(table)
+}
+
+/// Cache each selected day's puzzle input under `inputs/` without solving anything, so later
+/// `run`/`all`/`bench` invocations (and manual inspection) can work from the cached file instead
+/// of hitting the network. `input::fetch` already skips the request entirely when the file is
+/// already on disk, so running this twice is a no-op the second time.
+fn download(args: &[String]) -> Result<String, RunnerError> {
+    let days = select_days(args)?;
+    let example = args.iter().any(|arg| arg == "--example");
+
+    let mut lines = Vec::new();
+    for day in days {
+        input::fetch(YEAR, day, example)?;
+        lines.push(format!("day {day:>2}: cached under `inputs/`"));
+    }
+
+    Ok(lines.join("\n"))
+}
+
+fn format_duration(duration: std::time::Duration) -> String {
+    format!("{:.3}ms", duration.as_secs_f64() * 1000.0)
+}
+
+fn bench(args: &[String]) -> Result<String, RunnerError> {
+    let days = match flag_value(args, "-d") {
+        Some(spec) => parse_days(&spec)?,
+        None => registry().days(YEAR),
+    };
+    let repeats: usize = flag_value(args, "-n")
+        .map(|n| n.parse().map_err(|_| RunnerError::MissingArguments))
+        .transpose()?
+        .unwrap_or(1);
+    let example = args.iter().any(|arg| arg == "--example");
+
+    let mut rows = Vec::new();
+    for day in days {
+        let input = get_input(day, example, false)?;
+        let timings = registry().bench(YEAR, day, &input, repeats)?;
+        rows.push((day, timings));
+    }
+
+    let mut table = format!(
+        "{:>3} | {:>10} | {:>10} | {:>10} | {:>10}\n",
+        "day", "parse", "part1", "part2", "total"
+    );
+    for (day, timings) in &rows {
+        table += &format!(
+            "{:>3} | {:>10} | {:>10} | {:>10} | {:>10}\n",
+            day,
+            format_duration(timings.parse),
+            format_duration(timings.part_one),
+            format_duration(timings.part_two),
+            format_duration(timings.total()),
+        );
+    }
+    let total: std::time::Duration = rows.iter().map(|(_, t)| t.total()).sum();
+    table += &format!("total elapsed: {}", format_duration(total));
+
+    Ok(table)
+}
+
+fn main_inner() -> Result<String, RunnerError> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let subcommand = args.first().ok_or(RunnerError::MissingArguments)?;
+    let rest = &args[1..];
+
+    match &subcommand[..] {
+        "run" => run(rest),
+        "all" => all(rest),
+        "bench" => bench(rest),
+        "download" => download(rest),
+        _ => Err(RunnerError::UnknownSubcommand {
+            subcommand: subcommand.to_string(),
+        }),
+    }
+}
+
+fn main() {
+    match main_inner() {
+        Ok(answer) => println!("{}", answer),
+        Err(err) => {
+            eprintln!("{}", err);
+            exit(1);
+        }
+    }
+}